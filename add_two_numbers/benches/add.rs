@@ -0,0 +1,71 @@
+use add_two_numbers::add_two_numbers;
+use add_two_numbers::streaming::{sum_digits, PackedDecimal18, PackedDecimal9};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::Rng;
+
+fn random_digits(len: usize) -> Vec<i32> {
+  let mut rng = rand::thread_rng();
+
+  (0..len).map(|_| rng.gen_range(0..=9)).collect()
+}
+
+fn add(c: &mut Criterion) {
+  let mut group = c.benchmark_group("add");
+
+  for len in [1_000, 100_000, 1_000_000] {
+    let left = random_digits(len);
+    let right = random_digits(len);
+
+    group.throughput(Throughput::Elements(len as u64));
+
+    group.bench_with_input(
+      BenchmarkId::new("add_two_numbers", len),
+      &(&left, &right),
+      |b, (left, right)| {
+        b.iter_batched(
+          || ((*left).clone(), (*right).clone()),
+          |(left, right)| add_two_numbers(left, right),
+          criterion::BatchSize::LargeInput,
+        )
+      },
+    );
+
+    group.bench_with_input(
+      BenchmarkId::new("sum_digits", len),
+      &(&left, &right),
+      |b, (left, right)| {
+        b.iter(|| {
+          sum_digits(left.iter().rev().copied(), right.iter().rev().copied())
+            .fold(0, |acc, digit| acc ^ black_box(digit))
+        })
+      },
+    );
+
+    let (packed_left, packed_right) = (
+      PackedDecimal9::from_digits(&left),
+      PackedDecimal9::from_digits(&right),
+    );
+
+    group.bench_with_input(
+      BenchmarkId::new("packed_9_digits", len),
+      &(&packed_left, &packed_right),
+      |b, (left, right)| b.iter(|| *left + *right),
+    );
+
+    let (packed_left, packed_right) = (
+      PackedDecimal18::from_digits(&left),
+      PackedDecimal18::from_digits(&right),
+    );
+
+    group.bench_with_input(
+      BenchmarkId::new("packed_18_digits", len),
+      &(&packed_left, &packed_right),
+      |b, (left, right)| b.iter(|| *left + *right),
+    );
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, add);
+criterion_main!(benches);
@@ -0,0 +1,254 @@
+//! Evaluates expressions such as `123 + 456 + 7` where every operand is an
+//! arbitrarily long number written in a given radix.
+//!
+//! Digits above 9 are written with letters, `a` (or `A`) is 10 and `z` is 35.
+
+use thiserror::Error;
+
+use crate::radix;
+use crate::try_add_two_numbers_in_radix;
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum ExpressionErrorKind {
+  #[error("expected a number")]
+  ExpectedNumber,
+  #[error("expected `+`")]
+  ExpectedPlus,
+  #[error("invalid digit {character:?} for radix {radix}")]
+  InvalidDigit { character: char, radix: u32 },
+}
+
+/// An error in an expression. `column` is 1-based and counts characters.
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+#[error("column {column}: {kind}")]
+pub struct ExpressionError {
+  pub column: usize,
+  pub kind: ExpressionErrorKind,
+}
+
+/// Returns the digits of the sum of every number in the expression, most
+/// significant digit first.
+///
+/// # Panics
+///
+/// Panics if `radix` is not between 2 and 36.
+pub fn evaluate(expression: &str, radix: u32) -> Result<Vec<i32>, ExpressionError> {
+  let mut sum: Option<Vec<i32>> = None;
+
+  for number in parse(expression, radix)? {
+    sum = Some(match sum {
+      None => number,
+      Some(sum) => try_add_two_numbers_in_radix(sum, number, radix)
+        .expect("operands are validated while parsing"),
+    });
+  }
+
+  // `parse` never returns an empty list of numbers.
+  Ok(sum.unwrap())
+}
+
+/// Formats digits in `radix`, using lowercase letters for digits above 9.
+///
+/// # Panics
+///
+/// Panics if `radix` is not between 2 and 36 or if a digit is not between 0
+/// and `radix - 1`.
+pub fn format_digits(digits: &[i32], radix: u32) -> String {
+  radix::assert_radix_is_valid(radix);
+
+  digits
+    .iter()
+    .map(|digit| {
+      u32::try_from(*digit)
+        .ok()
+        .and_then(|digit| std::char::from_digit(digit, radix))
+        .unwrap_or_else(|| panic!("numbers must be between 0 and {}", radix - 1))
+    })
+    .collect()
+}
+
+fn parse(expression: &str, radix: u32) -> Result<Vec<Vec<i32>>, ExpressionError> {
+  radix::assert_radix_is_valid(radix);
+
+  let mut numbers = vec![];
+
+  let mut chars = expression.chars().enumerate().peekable();
+
+  loop {
+    skip_whitespace(&mut chars);
+
+    let mut number = vec![];
+
+    while let Some((column, character)) = chars.peek().copied() {
+      if !character.is_alphanumeric() {
+        break;
+      }
+
+      match character.to_digit(radix) {
+        Some(digit) => number.push(digit as i32),
+        None => {
+          return Err(ExpressionError {
+            column: column + 1,
+            kind: ExpressionErrorKind::InvalidDigit { character, radix },
+          })
+        }
+      }
+
+      chars.next();
+    }
+
+    if number.is_empty() {
+      return Err(ExpressionError {
+        column: column_of_next(&mut chars, expression),
+        kind: ExpressionErrorKind::ExpectedNumber,
+      });
+    }
+
+    numbers.push(number);
+
+    skip_whitespace(&mut chars);
+
+    match chars.next() {
+      None => return Ok(numbers),
+      Some((_, '+')) => {}
+      Some((column, _)) => {
+        return Err(ExpressionError {
+          column: column + 1,
+          kind: ExpressionErrorKind::ExpectedPlus,
+        })
+      }
+    }
+  }
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)>>) {
+  while chars
+    .peek()
+    .is_some_and(|(_, character)| character.is_whitespace())
+  {
+    chars.next();
+  }
+}
+
+/// Column of the next character or the column right after the end of the
+/// expression if there are no characters left.
+fn column_of_next(
+  chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)>>,
+  expression: &str,
+) -> usize {
+  match chars.peek() {
+    Some((column, _)) => column + 1,
+    None => expression.chars().count() + 1,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn error(column: usize, kind: ExpressionErrorKind) -> Result<Vec<i32>, ExpressionError> {
+    Err(ExpressionError { column, kind })
+  }
+
+  #[test]
+  fn evaluates_sums() {
+    assert_eq!(Ok(vec![5, 7, 9]), evaluate("123 + 456", 10));
+    assert_eq!(Ok(vec![5, 8, 6]), evaluate("123+456+7", 10));
+    assert_eq!(Ok(vec![4, 2]), evaluate("  42  ", 10));
+    assert_eq!(
+      Ok(vec![
+        1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+      ]),
+      evaluate("99999999999999999999 + 1", 10)
+    );
+  }
+
+  #[test]
+  fn evaluates_sums_in_other_radixes() {
+    assert_eq!(Ok(vec![1, 0, 0]), evaluate("11 + 1", 2));
+    assert_eq!(Ok(vec![1, 0, 14]), evaluate("ff + F", 16));
+    assert_eq!("10e", format_digits(&evaluate("ff + f", 16).unwrap(), 16));
+  }
+
+  #[test]
+  fn reports_the_column_of_malformed_input() {
+    assert_eq!(
+      error(1, ExpressionErrorKind::ExpectedNumber),
+      evaluate("", 10)
+    );
+    assert_eq!(
+      error(4, ExpressionErrorKind::ExpectedNumber),
+      evaluate("1 +", 10)
+    );
+    assert_eq!(
+      error(4, ExpressionErrorKind::ExpectedNumber),
+      evaluate("1 ++ 2", 10)
+    );
+    assert_eq!(
+      error(1, ExpressionErrorKind::ExpectedNumber),
+      evaluate("+1", 10)
+    );
+    assert_eq!(
+      error(3, ExpressionErrorKind::ExpectedPlus),
+      evaluate("1 2", 10)
+    );
+    assert_eq!(
+      error(3, ExpressionErrorKind::ExpectedPlus),
+      evaluate("1 - 2", 10)
+    );
+    assert_eq!(
+      error(2, ExpressionErrorKind::ExpectedPlus),
+      evaluate("1-2", 10)
+    );
+    assert_eq!(
+      error(5, ExpressionErrorKind::ExpectedNumber),
+      evaluate("1 + ?", 10)
+    );
+    assert_eq!(
+      error(
+        5,
+        ExpressionErrorKind::InvalidDigit {
+          character: 'x',
+          radix: 10
+        }
+      ),
+      evaluate("1 + x", 10)
+    );
+    assert_eq!(
+      error(
+        2,
+        ExpressionErrorKind::InvalidDigit {
+          character: '2',
+          radix: 2
+        }
+      ),
+      evaluate("12", 2)
+    );
+  }
+
+  #[test]
+  fn columns_count_characters_not_bytes() {
+    assert_eq!(
+      error(5, ExpressionErrorKind::ExpectedNumber),
+      evaluate("1 +\u{a0}?", 10)
+    );
+    assert_eq!(
+      error(
+        5,
+        ExpressionErrorKind::InvalidDigit {
+          character: 'é',
+          radix: 10
+        }
+      ),
+      evaluate("1 + é", 10)
+    );
+  }
+
+  #[test]
+  fn error_messages() {
+    assert_eq!(
+      "column 5: invalid digit 'x' for radix 10",
+      evaluate("1 + x", 10).unwrap_err().to_string()
+    );
+  }
+}
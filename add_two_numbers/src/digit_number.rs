@@ -0,0 +1,564 @@
+//! A non-negative arbitrary-precision number stored as a list of decimal
+//! digits, most significant digit first, the same representation
+//! `add_two_numbers` works with.
+//!
+//! The arithmetic is done on little-endian copies of the digits because
+//! carries and borrows move from the least significant digit upwards.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Operands with fewer digits than this are multiplied with the schoolbook
+/// algorithm, bigger ones are split with Karatsuba.
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum ParseDigitNumberError {
+  #[error("cannot parse a number from an empty string")]
+  Empty,
+  #[error("invalid digit {character:?} at index {index}")]
+  InvalidDigit { index: usize, character: char },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DigitNumber {
+  /// Most significant digit first, without leading zeros.
+  /// Zero is represented as `[0]`.
+  digits: Vec<i32>,
+}
+
+impl DigitNumber {
+  pub fn zero() -> Self {
+    Self { digits: vec![0] }
+  }
+
+  /// Builds a number from its digits, most significant digit first.
+  /// Leading zeros are removed and an empty list is zero.
+  ///
+  /// # Panics
+  ///
+  /// Panics if any digit is not between 0 and 9.
+  pub fn from_digits(digits: Vec<i32>) -> Self {
+    if digits.iter().any(|digit| !(0..=9).contains(digit)) {
+      panic!("numbers must be between 0 and 9");
+    }
+
+    let mut little_endian = digits;
+    little_endian.reverse();
+
+    Self::from_little_endian(little_endian)
+  }
+
+  /// Digits of the number, most significant digit first.
+  pub fn digits(&self) -> &[i32] {
+    &self.digits
+  }
+
+  pub fn into_digits(self) -> Vec<i32> {
+    self.digits
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.digits == [0]
+  }
+
+  /// Returns `None` if `other` is greater than `self`
+  /// because the result would be negative.
+  pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+    if self < other {
+      return None;
+    }
+
+    Some(Self::from_little_endian(sub_magnitudes(
+      &self.little_endian(),
+      &other.little_endian(),
+    )))
+  }
+
+  /// Returns the quotient and the remainder of the division
+  /// or `None` if `divisor` is zero.
+  pub fn checked_div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+    if divisor.is_zero() {
+      return None;
+    }
+
+    let divisor = divisor.little_endian();
+
+    let mut quotient = Vec::with_capacity(self.digits.len());
+    let mut remainder: Vec<i32> = vec![];
+
+    // Long division: bring down one digit at a time and find
+    // how many times the divisor fits in the current remainder.
+    for &digit in &self.digits {
+      remainder.insert(0, digit);
+      trim(&mut remainder);
+
+      let mut times = 0;
+
+      while compare_magnitudes(&remainder, &divisor) != Ordering::Less {
+        remainder = sub_magnitudes(&remainder, &divisor);
+        times += 1;
+      }
+
+      quotient.push(times);
+    }
+
+    quotient.reverse();
+
+    Some((
+      Self::from_little_endian(quotient),
+      Self::from_little_endian(remainder),
+    ))
+  }
+
+  /// # Panics
+  ///
+  /// Panics if `divisor` is zero.
+  pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+    self
+      .checked_div_rem(divisor)
+      .expect("attempt to divide by zero")
+  }
+
+  fn from_little_endian(mut digits: Vec<i32>) -> Self {
+    trim(&mut digits);
+
+    if digits.is_empty() {
+      return Self::zero();
+    }
+
+    digits.reverse();
+
+    Self { digits }
+  }
+
+  fn little_endian(&self) -> Vec<i32> {
+    if self.is_zero() {
+      return vec![];
+    }
+
+    self.digits.iter().rev().copied().collect()
+  }
+}
+
+impl Default for DigitNumber {
+  fn default() -> Self {
+    Self::zero()
+  }
+}
+
+impl From<u64> for DigitNumber {
+  fn from(mut n: u64) -> Self {
+    let mut digits = vec![];
+
+    while n > 0 {
+      digits.push((n % 10) as i32);
+      n /= 10;
+    }
+
+    Self::from_little_endian(digits)
+  }
+}
+
+impl Ord for DigitNumber {
+  fn cmp(&self, other: &Self) -> Ordering {
+    // There are no leading zeros, so the longer number is the bigger one.
+    self
+      .digits
+      .len()
+      .cmp(&other.digits.len())
+      .then_with(|| self.digits.cmp(&other.digits))
+  }
+}
+
+impl PartialOrd for DigitNumber {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl FromStr for DigitNumber {
+  type Err = ParseDigitNumberError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.is_empty() {
+      return Err(ParseDigitNumberError::Empty);
+    }
+
+    let digits = s
+      .char_indices()
+      .map(|(index, character)| {
+        character
+          .to_digit(10)
+          .map(|digit| digit as i32)
+          .ok_or(ParseDigitNumberError::InvalidDigit { index, character })
+      })
+      .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Self::from_digits(digits))
+  }
+}
+
+impl fmt::Display for DigitNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for digit in &self.digits {
+      write!(f, "{}", digit)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl Add for &DigitNumber {
+  type Output = DigitNumber;
+
+  fn add(self, other: &DigitNumber) -> DigitNumber {
+    DigitNumber::from_little_endian(add_magnitudes(
+      &self.little_endian(),
+      &other.little_endian(),
+    ))
+  }
+}
+
+impl Sub for &DigitNumber {
+  type Output = DigitNumber;
+
+  /// # Panics
+  ///
+  /// Panics if `other` is greater than `self`.
+  fn sub(self, other: &DigitNumber) -> DigitNumber {
+    self
+      .checked_sub(other)
+      .expect("attempt to subtract with overflow")
+  }
+}
+
+impl Mul for &DigitNumber {
+  type Output = DigitNumber;
+
+  fn mul(self, other: &DigitNumber) -> DigitNumber {
+    DigitNumber::from_little_endian(karatsuba(&self.little_endian(), &other.little_endian()))
+  }
+}
+
+impl Div for &DigitNumber {
+  type Output = DigitNumber;
+
+  fn div(self, other: &DigitNumber) -> DigitNumber {
+    self.div_rem(other).0
+  }
+}
+
+impl Rem for &DigitNumber {
+  type Output = DigitNumber;
+
+  fn rem(self, other: &DigitNumber) -> DigitNumber {
+    self.div_rem(other).1
+  }
+}
+
+/// Implements the operator for owned values by forwarding to the
+/// implementation for references.
+macro_rules! forward_owned_binop {
+  ($($trait:ident::$method:ident),*) => {
+    $(
+      impl $trait for DigitNumber {
+        type Output = DigitNumber;
+
+        fn $method(self, other: DigitNumber) -> DigitNumber {
+          (&self).$method(&other)
+        }
+      }
+    )*
+  };
+}
+
+forward_owned_binop!(Add::add, Sub::sub, Mul::mul, Div::div, Rem::rem);
+
+/// Removes the most significant zeros from a little-endian digit list.
+/// Zero becomes an empty list.
+fn trim(digits: &mut Vec<i32>) {
+  while digits.last() == Some(&0) {
+    digits.pop();
+  }
+}
+
+/// Compares two trimmed little-endian digit lists.
+fn compare_magnitudes(left: &[i32], right: &[i32]) -> Ordering {
+  left
+    .len()
+    .cmp(&right.len())
+    .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+fn add_magnitudes(left: &[i32], right: &[i32]) -> Vec<i32> {
+  let mut result = Vec::with_capacity(std::cmp::max(left.len(), right.len()) + 1);
+
+  let mut carry = 0;
+
+  for i in 0..std::cmp::max(left.len(), right.len()) {
+    let sum = left.get(i).unwrap_or(&0) + right.get(i).unwrap_or(&0) + carry;
+
+    result.push(sum % 10);
+
+    carry = sum / 10;
+  }
+
+  if carry > 0 {
+    result.push(carry);
+  }
+
+  result
+}
+
+/// Subtracts `right` from `left`. `left` must not be smaller than `right`.
+fn sub_magnitudes(left: &[i32], right: &[i32]) -> Vec<i32> {
+  let mut result = Vec::with_capacity(left.len());
+
+  let mut borrow = 0;
+
+  for (i, left_digit) in left.iter().enumerate() {
+    let mut difference = left_digit - right.get(i).unwrap_or(&0) - borrow;
+
+    borrow = if difference < 0 {
+      difference += 10;
+      1
+    } else {
+      0
+    };
+
+    result.push(difference);
+  }
+
+  debug_assert_eq!(0, borrow, "left must not be smaller than right");
+
+  trim(&mut result);
+
+  result
+}
+
+fn schoolbook(left: &[i32], right: &[i32]) -> Vec<i32> {
+  if left.is_empty() || right.is_empty() {
+    return vec![];
+  }
+
+  let mut result = vec![0; left.len() + right.len()];
+
+  for (i, left_digit) in left.iter().enumerate() {
+    let mut carry = 0;
+
+    for (j, right_digit) in right.iter().enumerate() {
+      let product = result[i + j] + left_digit * right_digit + carry;
+
+      result[i + j] = product % 10;
+
+      carry = product / 10;
+    }
+
+    result[i + right.len()] += carry;
+  }
+
+  trim(&mut result);
+
+  result
+}
+
+/// Splits both numbers as `high * 10^m + low` and computes the product with
+/// three recursive multiplications instead of four:
+/// `low * low`, `high * high` and `(low + high) * (low + high)`.
+fn karatsuba(left: &[i32], right: &[i32]) -> Vec<i32> {
+  if std::cmp::min(left.len(), right.len()) < KARATSUBA_THRESHOLD {
+    return schoolbook(left, right);
+  }
+
+  let m = std::cmp::max(left.len(), right.len()) / 2;
+
+  let (left_low, left_high) = split(left, m);
+  let (right_low, right_high) = split(right, m);
+
+  let low = karatsuba(&left_low, &right_low);
+  let high = karatsuba(&left_high, &right_high);
+  let middle = karatsuba(
+    &add_magnitudes(&left_low, &left_high),
+    &add_magnitudes(&right_low, &right_high),
+  );
+  let middle = sub_magnitudes(&sub_magnitudes(&middle, &low), &high);
+
+  let mut result = add_magnitudes(&low, &shift(&middle, m));
+  result = add_magnitudes(&result, &shift(&high, 2 * m));
+
+  trim(&mut result);
+
+  result
+}
+
+fn split(digits: &[i32], at: usize) -> (Vec<i32>, Vec<i32>) {
+  let at = std::cmp::min(at, digits.len());
+
+  let mut low = digits[..at].to_vec();
+  trim(&mut low);
+
+  (low, digits[at..].to_vec())
+}
+
+/// Multiplies a little-endian digit list by `10^places`.
+fn shift(digits: &[i32], places: usize) -> Vec<i32> {
+  if digits.is_empty() {
+    return vec![];
+  }
+
+  let mut result = vec![0; places];
+  result.extend_from_slice(digits);
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  fn number(s: &str) -> DigitNumber {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn leading_zeros_are_removed() {
+    assert_eq!(
+      vec![1, 2],
+      DigitNumber::from_digits(vec![0, 0, 1, 2]).into_digits()
+    );
+    assert_eq!(vec![0], DigitNumber::from_digits(vec![0, 0]).into_digits());
+    assert_eq!(vec![0], DigitNumber::from_digits(vec![]).into_digits());
+  }
+
+  #[test]
+  #[should_panic(expected = "numbers must be between 0 and 9")]
+  fn digits_must_be_between_0_and_9() {
+    DigitNumber::from_digits(vec![1, 10]);
+  }
+
+  #[test]
+  fn parse_errors() {
+    assert_eq!(Err(ParseDigitNumberError::Empty), "".parse::<DigitNumber>());
+    assert_eq!(
+      Err(ParseDigitNumberError::InvalidDigit {
+        index: 2,
+        character: 'x'
+      }),
+      "12x4".parse::<DigitNumber>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::InvalidDigit {
+        index: 0,
+        character: '-'
+      }),
+      "-1".parse::<DigitNumber>()
+    );
+  }
+
+  #[test]
+  fn display() {
+    assert_eq!("0", DigitNumber::zero().to_string());
+    assert_eq!("1200", number("0001200").to_string());
+  }
+
+  #[test]
+  fn ordering() {
+    assert!(number("9") < number("10"));
+    assert!(number("123") < number("124"));
+    assert!(number("1000") > number("999"));
+    assert_eq!(Ordering::Equal, number("007").cmp(&number("7")));
+  }
+
+  #[test]
+  fn subtraction() {
+    assert_eq!(number("0"), number("5") - number("5"));
+    assert_eq!(number("99"), number("100") - number("1"));
+    assert_eq!(None, number("1").checked_sub(&number("2")));
+  }
+
+  #[test]
+  #[should_panic(expected = "attempt to subtract with overflow")]
+  fn subtraction_cannot_go_below_zero() {
+    let _ = number("1") - number("2");
+  }
+
+  #[test]
+  fn multiplication() {
+    assert_eq!(number("0"), number("0") * number("123"));
+    assert_eq!(number("56088"), number("123") * number("456"));
+    assert_eq!(
+      number("121932631137021795226185032733622923332237463801111263526900"),
+      number("123456789012345678901234567890") * number("987654321098765432109876543210")
+    );
+  }
+
+  #[test]
+  fn division() {
+    assert_eq!(
+      (number("0"), number("7")),
+      number("7").div_rem(&number("8"))
+    );
+    assert_eq!(
+      (number("41"), number("1")),
+      number("124").div_rem(&number("3"))
+    );
+    assert_eq!(number("100"), number("1000") / number("10"));
+    assert_eq!(number("4"), number("1000") % number("12"));
+    assert_eq!(None, number("1").checked_div_rem(&DigitNumber::zero()));
+  }
+
+  #[test]
+  #[should_panic(expected = "attempt to divide by zero")]
+  fn division_by_zero() {
+    let _ = number("1") / DigitNumber::zero();
+  }
+
+  fn digit_number() -> impl Strategy<Value = DigitNumber> {
+    proptest::collection::vec(0..=9, 0..200).prop_map(DigitNumber::from_digits)
+  }
+
+  proptest! {
+    #[test]
+    fn matches_u64_arithmetic(left in 0..u64::from(u32::MAX), right in 1..u64::from(u32::MAX)) {
+      let (l, r) = (DigitNumber::from(left), DigitNumber::from(right));
+
+      prop_assert_eq!(DigitNumber::from(left + right), &l + &r);
+      prop_assert_eq!(DigitNumber::from(left * right), &l * &r);
+      prop_assert_eq!(DigitNumber::from(left / right), &l / &r);
+      prop_assert_eq!(DigitNumber::from(left % right), &l % &r);
+      prop_assert_eq!(left.cmp(&right), l.cmp(&r));
+    }
+
+    #[test]
+    fn karatsuba_matches_schoolbook(left in digit_number(), right in digit_number()) {
+      let expected = DigitNumber::from_little_endian(schoolbook(&left.little_endian(), &right.little_endian()));
+
+      prop_assert_eq!(expected, &left * &right);
+    }
+
+    #[test]
+    fn division_is_the_inverse_of_multiplication(dividend in digit_number(), divisor in digit_number()) {
+      prop_assume!(!divisor.is_zero());
+
+      let (quotient, remainder) = dividend.div_rem(&divisor);
+
+      prop_assert!(remainder < divisor);
+      prop_assert_eq!(dividend, &(&quotient * &divisor) + &remainder);
+    }
+
+    #[test]
+    fn subtraction_is_the_inverse_of_addition(left in digit_number(), right in digit_number()) {
+      prop_assert_eq!(&left, &(&(&left + &right) - &right));
+    }
+
+    #[test]
+    fn display_and_from_str_roundtrip(n in digit_number()) {
+      prop_assert_eq!(n.clone(), n.to_string().parse::<DigitNumber>().unwrap());
+    }
+  }
+}
@@ -0,0 +1,381 @@
+//! Non-negative fixed-point decimals stored as two digit lists, one for the
+//! integer part and one for the fractional part, both most significant digit
+//! first.
+//!
+//! The number of fractional digits is the scale of the number, so `1.50` and
+//! `1.5` have different scales and are not equal. Use [`FixedDecimal::round`]
+//! to bring numbers to the same scale.
+
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+
+use crate::digit_number::{DigitNumber, ParseDigitNumberError};
+
+/// How the digits that do not fit in the target scale are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+  /// Drops the extra digits. `1.29` becomes `1.2`.
+  Truncate,
+  /// Rounds up when any dropped digit is not zero. `1.21` becomes `1.3`.
+  Up,
+  /// Rounds to the nearest number, ties round up. `1.25` becomes `1.3`.
+  HalfUp,
+  /// Rounds to the nearest number, ties round to the even digit.
+  /// `1.25` becomes `1.2` and `1.35` becomes `1.4`.
+  HalfEven,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedDecimal {
+  /// Without leading zeros, zero is `[0]`.
+  integer: Vec<i32>,
+  /// Trailing zeros are kept because they are part of the scale.
+  fraction: Vec<i32>,
+}
+
+impl FixedDecimal {
+  /// # Panics
+  ///
+  /// Panics if any digit is not between 0 and 9.
+  pub fn new(integer: Vec<i32>, fraction: Vec<i32>) -> Self {
+    if fraction.iter().any(|digit| !(0..=9).contains(digit)) {
+      panic!("numbers must be between 0 and 9");
+    }
+
+    Self {
+      integer: DigitNumber::from_digits(integer).into_digits(),
+      fraction,
+    }
+  }
+
+  pub fn integer(&self) -> &[i32] {
+    &self.integer
+  }
+
+  pub fn fraction(&self) -> &[i32] {
+    &self.fraction
+  }
+
+  /// Number of fractional digits.
+  pub fn scale(&self) -> usize {
+    self.fraction.len()
+  }
+
+  /// Same as [`FixedDecimal::round`] with [`RoundingMode::Truncate`].
+  pub fn truncate(&self, scale: usize) -> Self {
+    self.round(scale, RoundingMode::Truncate)
+  }
+
+  /// Returns the number with exactly `scale` fractional digits.
+  /// Zeros are appended when the number has fewer fractional digits.
+  pub fn round(&self, scale: usize, mode: RoundingMode) -> Self {
+    if scale >= self.scale() {
+      let mut fraction = self.fraction.clone();
+      fraction.resize(scale, 0);
+
+      return Self {
+        integer: self.integer.clone(),
+        fraction,
+      };
+    }
+
+    let (kept, dropped) = self.fraction.split_at(scale);
+
+    let truncated = Self {
+      integer: self.integer.clone(),
+      fraction: kept.to_vec(),
+    };
+
+    let first_dropped = dropped[0];
+    let rest_is_zero = dropped[1..].iter().all(|digit| *digit == 0);
+    let last_kept = *kept.last().or(self.integer.last()).unwrap();
+
+    let round_up = match mode {
+      RoundingMode::Truncate => false,
+      RoundingMode::Up => first_dropped != 0 || !rest_is_zero,
+      RoundingMode::HalfUp => first_dropped >= 5,
+      RoundingMode::HalfEven => {
+        first_dropped > 5 || (first_dropped == 5 && (!rest_is_zero || last_kept % 2 == 1))
+      }
+    };
+
+    if !round_up {
+      return truncated;
+    }
+
+    let mut unit = vec![0; scale];
+
+    let one = match unit.last_mut() {
+      Some(digit) => {
+        *digit = 1;
+        Self::new(vec![0], unit)
+      }
+      None => Self::new(vec![1], vec![]),
+    };
+
+    &truncated + &one
+  }
+}
+
+impl From<DigitNumber> for FixedDecimal {
+  fn from(n: DigitNumber) -> Self {
+    Self {
+      integer: n.into_digits(),
+      fraction: vec![],
+    }
+  }
+}
+
+impl FromStr for FixedDecimal {
+  type Err = ParseDigitNumberError;
+
+  /// Accepts `123` or `123.45`.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (integer, fraction) = match s.split_once('.') {
+      Some((integer, fraction)) => (integer, Some(fraction)),
+      None => (s, None),
+    };
+
+    let integer = integer.parse::<DigitNumber>()?;
+
+    let fraction = match fraction {
+      None => vec![],
+      Some("") => return Err(ParseDigitNumberError::Empty),
+      Some(fraction) => fraction
+        .char_indices()
+        .map(|(index, character)| {
+          character.to_digit(10).map(|digit| digit as i32).ok_or(
+            ParseDigitNumberError::InvalidDigit {
+              // The fraction starts after the integer part and the point.
+              index: s.len() - fraction.len() + index,
+              character,
+            },
+          )
+        })
+        .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    Ok(Self {
+      integer: integer.into_digits(),
+      fraction,
+    })
+  }
+}
+
+impl fmt::Display for FixedDecimal {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for digit in &self.integer {
+      write!(f, "{}", digit)?;
+    }
+
+    if !self.fraction.is_empty() {
+      write!(f, ".")?;
+
+      for digit in &self.fraction {
+        write!(f, "{}", digit)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl Add for &FixedDecimal {
+  type Output = FixedDecimal;
+
+  /// The result has the bigger scale of the two operands.
+  fn add(self, other: &FixedDecimal) -> FixedDecimal {
+    let scale = std::cmp::max(self.scale(), other.scale());
+
+    // Pad the shorter fraction with zeros so both are aligned on the point.
+    let (mut left_fraction, mut right_fraction) = (self.fraction.clone(), other.fraction.clone());
+    left_fraction.resize(scale, 0);
+    right_fraction.resize(scale, 0);
+
+    let mut fraction = vec![0; scale];
+
+    let mut carry = 0;
+
+    for i in (0..scale).rev() {
+      let sum = left_fraction[i] + right_fraction[i] + carry;
+
+      fraction[i] = sum % 10;
+
+      carry = sum / 10;
+    }
+
+    let mut integer = vec![];
+
+    let (mut left, mut right) = (self.integer.iter().rev(), other.integer.iter().rev());
+
+    loop {
+      let (left_digit, right_digit) = match (left.next(), right.next()) {
+        (None, None) => break,
+        (left_digit, right_digit) => (*left_digit.unwrap_or(&0), *right_digit.unwrap_or(&0)),
+      };
+
+      // The carry from the fractional part goes into the units digit.
+      let sum = left_digit + right_digit + carry;
+
+      integer.push(sum % 10);
+
+      carry = sum / 10;
+    }
+
+    if carry > 0 {
+      integer.push(carry);
+    }
+
+    integer.reverse();
+
+    FixedDecimal::new(integer, fraction)
+  }
+}
+
+impl Add for FixedDecimal {
+  type Output = FixedDecimal;
+
+  fn add(self, other: FixedDecimal) -> FixedDecimal {
+    &self + &other
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  fn number(s: &str) -> FixedDecimal {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn aligns_on_the_decimal_point() {
+    assert_eq!(number("133.449"), number("123.45") + number("9.999"));
+    assert_eq!(number("10.5"), number("10") + number("0.5"));
+    assert_eq!(number("0.0003"), number("0.0001") + number("0.0002"));
+  }
+
+  #[test]
+  fn carries_from_the_fractional_part_into_the_integer_part() {
+    assert_eq!(number("1.0"), number("0.5") + number("0.5"));
+    assert_eq!(number("100.00"), number("99.99") + number("0.01"));
+    assert_eq!(number("1000.000"), number("999.999") + number("0.001"));
+  }
+
+  #[test]
+  fn scale_is_kept() {
+    assert_eq!("1.50", (number("1.25") + number("0.25")).to_string());
+    assert_eq!(2, number("0.10").scale());
+    assert_eq!(0, number("12").scale());
+    assert_ne!(number("1.5"), number("1.50"));
+  }
+
+  #[test]
+  fn parsing() {
+    assert_eq!(vec![1, 2], number("0012.34").integer());
+    assert_eq!(vec![3, 4], number("0012.34").fraction());
+    assert_eq!(
+      Err(ParseDigitNumberError::Empty),
+      ".5".parse::<FixedDecimal>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::Empty),
+      "5.".parse::<FixedDecimal>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::InvalidDigit {
+        index: 4,
+        character: 'x'
+      }),
+      "12.3x".parse::<FixedDecimal>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::InvalidDigit {
+        index: 3,
+        character: '.'
+      }),
+      "1.2.3".parse::<FixedDecimal>()
+    );
+  }
+
+  #[test]
+  fn rounding() {
+    let cases = [
+      ("1.25", 1, RoundingMode::Truncate, "1.2"),
+      ("1.29", 1, RoundingMode::Truncate, "1.2"),
+      ("1.21", 1, RoundingMode::Up, "1.3"),
+      ("1.20", 1, RoundingMode::Up, "1.2"),
+      ("1.25", 1, RoundingMode::HalfUp, "1.3"),
+      ("1.24", 1, RoundingMode::HalfUp, "1.2"),
+      ("1.25", 1, RoundingMode::HalfEven, "1.2"),
+      ("1.35", 1, RoundingMode::HalfEven, "1.4"),
+      ("1.251", 1, RoundingMode::HalfEven, "1.3"),
+      ("2.5", 0, RoundingMode::HalfEven, "2"),
+      ("3.5", 0, RoundingMode::HalfEven, "4"),
+      ("9.99", 1, RoundingMode::HalfUp, "10.0"),
+      ("99.5", 0, RoundingMode::HalfUp, "100"),
+      ("0.001", 2, RoundingMode::Up, "0.01"),
+      ("1.5", 3, RoundingMode::Truncate, "1.500"),
+    ];
+
+    for (input, scale, mode, expected) in cases {
+      assert_eq!(
+        expected,
+        number(input).round(scale, mode).to_string(),
+        "{} rounded to {} digits with {:?}",
+        input,
+        scale,
+        mode
+      );
+    }
+
+    assert_eq!(number("1.2"), number("1.29").truncate(1));
+  }
+
+  fn fixed_decimal() -> impl Strategy<Value = (u64, usize)> {
+    (0..u64::from(u32::MAX), 0..6_usize)
+  }
+
+  fn from_scaled((value, scale): (u64, usize)) -> FixedDecimal {
+    let digits = format!("{:0width$}", value, width = scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+
+    if fraction.is_empty() {
+      number(integer)
+    } else {
+      number(&format!("{}.{}", integer, fraction))
+    }
+  }
+
+  proptest! {
+    #[test]
+    fn matches_integer_addition_of_scaled_values(left in fixed_decimal(), right in fixed_decimal()) {
+      let scale = std::cmp::max(left.1, right.1);
+      let expected = left.0 * 10_u64.pow((scale - left.1) as u32) + right.0 * 10_u64.pow((scale - right.1) as u32);
+
+      prop_assert_eq!(from_scaled((expected, scale)), from_scaled(left) + from_scaled(right));
+    }
+
+    #[test]
+    fn display_and_from_str_roundtrip(n in fixed_decimal()) {
+      let n = from_scaled(n);
+
+      prop_assert_eq!(n.clone(), n.to_string().parse::<FixedDecimal>().unwrap());
+    }
+
+    #[test]
+    fn rounding_never_moves_more_than_one_unit(n in fixed_decimal(), scale in 0..6_usize) {
+      let n = from_scaled(n);
+
+      let truncated = n.truncate(scale);
+      let up = n.round(scale, RoundingMode::Up);
+
+      for mode in [RoundingMode::HalfUp, RoundingMode::HalfEven] {
+        let rounded = n.round(scale, mode);
+        prop_assert!(rounded == truncated || rounded == up);
+      }
+    }
+  }
+}
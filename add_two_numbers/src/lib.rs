@@ -0,0 +1,350 @@
+pub mod calculator;
+pub mod digit_number;
+pub mod fixed_point;
+pub mod radix;
+pub mod signed_digit_number;
+pub mod streaming;
+
+use std::collections::LinkedList;
+use std::fmt;
+
+use thiserror::Error;
+
+pub use digit_number::DigitNumber;
+pub use fixed_point::{FixedDecimal, RoundingMode};
+pub use signed_digit_number::{Sign, SignedDigitNumber};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operand {
+  Left,
+  Right,
+}
+
+impl fmt::Display for Operand {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Operand::Left => write!(f, "left"),
+      Operand::Right => write!(f, "right"),
+    }
+  }
+}
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum AddError {
+  #[error("the {operand} number is empty")]
+  Empty { operand: Operand },
+  #[error(
+    "numbers must be between 0 and {}. the {operand} number has {value} at index {index}",
+    radix - 1
+  )]
+  InvalidDigit {
+    operand: Operand,
+    index: usize,
+    value: i32,
+    radix: u32,
+  },
+  #[error(
+    "radix must be between {} and {}. got {radix}",
+    radix::MIN_RADIX,
+    radix::MAX_RADIX
+  )]
+  InvalidRadix { radix: u32 },
+}
+
+/// # Panics
+///
+/// Panics if a digit is not between 0 and 9.
+/// Use [`try_add_two_numbers`] to get an error instead.
+pub fn add_two_numbers(left: Vec<i32>, right: Vec<i32>) -> Vec<i32> {
+  add_two_numbers_in_radix(left, right, 10)
+}
+
+/// Same as [`add_two_numbers`] but the digits of both numbers and of the
+/// result are in base `radix`.
+///
+/// # Panics
+///
+/// Panics if `radix` is not between 2 and 36 or if a digit is not between 0
+/// and `radix - 1`. Use [`try_add_two_numbers_in_radix`] to get an error instead.
+pub fn add_two_numbers_in_radix(left: Vec<i32>, right: Vec<i32>, radix: u32) -> Vec<i32> {
+  match try_add_two_numbers_in_radix(left, right, radix) {
+    Ok(sum) => sum,
+    Err(AddError::Empty { .. }) => vec![],
+    Err(error) => panic!("{}", error),
+  }
+}
+
+pub fn try_add_two_numbers(left: Vec<i32>, right: Vec<i32>) -> Result<Vec<i32>, AddError> {
+  try_add_two_numbers_in_radix(left, right, 10)
+}
+
+pub fn try_add_two_numbers_in_radix(
+  mut left: Vec<i32>,
+  mut right: Vec<i32>,
+  radix: u32,
+) -> Result<Vec<i32>, AddError> {
+  if !(radix::MIN_RADIX..=radix::MAX_RADIX).contains(&radix) {
+    return Err(AddError::InvalidRadix { radix });
+  }
+
+  // Both operands are checked for emptiness before any digit, so an empty
+  // operand wins over an invalid digit in the other one.
+  if left.is_empty() {
+    return Err(AddError::Empty {
+      operand: Operand::Left,
+    });
+  }
+
+  if right.is_empty() {
+    return Err(AddError::Empty {
+      operand: Operand::Right,
+    });
+  }
+
+  validate(&left, Operand::Left, radix)?;
+  validate(&right, Operand::Right, radix)?;
+
+  let radix = radix as i32;
+
+  left.reverse();
+  right.reverse();
+
+  let mut result = LinkedList::new();
+
+  let mut carry = 0;
+
+  for i in 0..std::cmp::max(left.len(), right.len()) {
+    let left_digit = if left.len() > i { left[i] } else { 0 };
+
+    let right_digit = if right.len() > i { right[i] } else { 0 };
+
+    let sum = left_digit + right_digit + carry;
+
+    result.push_front(sum % radix);
+
+    carry = sum / radix;
+  }
+
+  if carry > 0 {
+    result.push_front(carry);
+  }
+
+  Ok(result.into_iter().collect())
+}
+
+fn validate(digits: &[i32], operand: Operand, radix: u32) -> Result<(), AddError> {
+  match digits
+    .iter()
+    .position(|digit| !(0..radix as i32).contains(digit))
+  {
+    None => Ok(()),
+    Some(index) => Err(AddError::InvalidDigit {
+      operand,
+      index,
+      value: digits[index],
+      radix,
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  #[test]
+  fn empty_lists_should_return_empty_list() {
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![]));
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![1]));
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![1], vec![]));
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![1, 2, 3]));
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![1, 2, 3], vec![]));
+    // An empty operand is checked before the digits of the other one.
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![-1], vec![]));
+    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![10]));
+  }
+
+  #[test]
+  fn should_add_numbers_with_a_single_digit() {
+    assert_eq!(vec![3], add_two_numbers(vec![1], vec![2]));
+    assert_eq!(vec![1, 1], add_two_numbers(vec![9], vec![2]));
+  }
+
+  #[test]
+  fn should_add_numbers_with_many_digits() {
+    assert_eq!(vec![5, 5], add_two_numbers(vec![2, 2], vec![3, 3]));
+    assert_eq!(vec![5, 2], add_two_numbers(vec![2, 9], vec![2, 3]));
+    assert_eq!(vec![4, 7, 6], add_two_numbers(vec![2, 9, 3], vec![1, 8, 3]));
+    assert_eq!(vec![4, 4, 7], add_two_numbers(vec![1, 7, 9], vec![2, 6, 8]));
+    assert_eq!(
+      vec![3, 7, 3, 3, 2],
+      add_two_numbers(vec![1, 9, 1, 7, 1], vec![1, 8, 1, 6, 1])
+    );
+  }
+
+  #[test]
+  fn should_add_numbers_with_digits_of_different_lengths() {
+    assert_eq!(vec![2, 5], add_two_numbers(vec![2, 2], vec![3]));
+    assert_eq!(vec![2, 5], add_two_numbers(vec![3], vec![2, 2]));
+    assert_eq!(vec![3, 1], add_two_numbers(vec![2, 2], vec![9]));
+    assert_eq!(vec![3, 1], add_two_numbers(vec![9], vec![2, 2]));
+  }
+
+  #[test]
+  fn should_add_numbers_in_other_radixes() {
+    assert_eq!(
+      vec![1, 0, 0],
+      add_two_numbers_in_radix(vec![1, 1], vec![1], 2)
+    );
+    assert_eq!(
+      vec![1, 0, 14],
+      add_two_numbers_in_radix(vec![15, 15], vec![15], 16)
+    );
+    assert_eq!(vec![1, 0], add_two_numbers_in_radix(vec![35], vec![1], 36));
+    assert_eq!(
+      add_two_numbers(vec![1, 9, 1, 7, 1], vec![1, 8, 1, 6, 1]),
+      add_two_numbers_in_radix(vec![1, 9, 1, 7, 1], vec![1, 8, 1, 6, 1], 10)
+    );
+  }
+
+  #[test]
+  fn reports_which_operand_is_empty() {
+    assert_eq!(
+      Err(AddError::Empty {
+        operand: Operand::Left
+      }),
+      try_add_two_numbers(vec![], vec![1])
+    );
+    assert_eq!(
+      Err(AddError::Empty {
+        operand: Operand::Right
+      }),
+      try_add_two_numbers(vec![1], vec![])
+    );
+    assert_eq!(
+      Err(AddError::Empty {
+        operand: Operand::Left
+      }),
+      try_add_two_numbers(vec![], vec![])
+    );
+    assert_eq!(
+      Err(AddError::Empty {
+        operand: Operand::Right
+      }),
+      try_add_two_numbers(vec![-1], vec![])
+    );
+  }
+
+  #[test]
+  fn reports_the_invalid_digit() {
+    assert_eq!(
+      Err(AddError::InvalidDigit {
+        operand: Operand::Left,
+        index: 1,
+        value: -3,
+        radix: 10
+      }),
+      try_add_two_numbers(vec![1, -3, 4], vec![1])
+    );
+    assert_eq!(
+      Err(AddError::InvalidDigit {
+        operand: Operand::Right,
+        index: 0,
+        value: 10,
+        radix: 10
+      }),
+      try_add_two_numbers(vec![1], vec![10, 2])
+    );
+    assert_eq!(
+      Err(AddError::InvalidDigit {
+        operand: Operand::Right,
+        index: 2,
+        value: 2,
+        radix: 2
+      }),
+      try_add_two_numbers_in_radix(vec![1], vec![1, 0, 2], 2)
+    );
+    assert_eq!(
+      "numbers must be between 0 and 9. the right number has 10 at index 0",
+      try_add_two_numbers(vec![1], vec![10, 2])
+        .unwrap_err()
+        .to_string()
+    );
+  }
+
+  #[test]
+  fn reports_an_invalid_radix() {
+    assert_eq!(
+      Err(AddError::InvalidRadix { radix: 37 }),
+      try_add_two_numbers_in_radix(vec![1], vec![1], 37)
+    );
+  }
+
+  #[test]
+  #[should_panic(expected = "numbers must be between 0 and 15")]
+  fn digits_must_be_smaller_than_the_radix() {
+    add_two_numbers_in_radix(vec![16], vec![1], 16);
+  }
+
+  #[test]
+  #[should_panic(expected = "radix must be between 2 and 36. got 1")]
+  fn radix_must_be_between_2_and_36() {
+    add_two_numbers_in_radix(vec![0], vec![0], 1);
+  }
+
+  prop_compose! {
+    fn list_contains_negative_number()(mut xs: Vec<i32>) -> Vec<i32> {
+      if !xs.is_empty() {
+        let i = rand::thread_rng().gen_range(0..xs.len());
+
+        if xs[i] > 0 {
+          xs[i] = -xs[i];
+        }
+      }
+
+      xs
+    }
+  }
+
+  proptest! {
+    #[test]
+    #[should_panic(expected = "numbers must be between 0 and 9")]
+    fn numbers_must_be_between_0_and_9(left in list_contains_negative_number(), right in list_contains_negative_number()) {
+      prop_assume!(!left.is_empty() && !right.is_empty());
+      add_two_numbers(left, right);
+    }
+
+    #[test]
+    fn try_add_two_numbers_returns_an_error_instead_of_panicking(
+      left in list_contains_negative_number(),
+      right in list_contains_negative_number()
+    ) {
+      prop_assume!(left.iter().chain(right.iter()).any(|digit| !(0..=9).contains(digit)));
+
+      let is_invalid_digit = matches!(
+        try_add_two_numbers(left, right),
+        Err(AddError::InvalidDigit { .. }) | Err(AddError::Empty { .. })
+      );
+
+      prop_assert!(is_invalid_digit);
+    }
+
+    #[test]
+    fn addition_does_not_depend_on_the_radix(
+      left in proptest::collection::vec(0..10, 1..50),
+      right in proptest::collection::vec(0..10, 1..50),
+      radix in radix::MIN_RADIX..=radix::MAX_RADIX
+    ) {
+      let sum = add_two_numbers_in_radix(
+        radix::convert_radix(&left, 10, radix),
+        radix::convert_radix(&right, 10, radix),
+        radix,
+      );
+
+      prop_assert_eq!(
+        radix::convert_radix(&add_two_numbers(left, right), 10, 10),
+        radix::convert_radix(&sum, radix, 10)
+      );
+    }
+  }
+}
@@ -1,104 +1,100 @@
-use std::collections::LinkedList;
+//! Adds arbitrarily long numbers.
+//!
+//! Every expression, such as `123 + 456`, is read from the arguments or, when
+//! there are none, from stdin, one expression per line. The sum of each
+//! expression is printed on its own line.
+//!
+//! Malformed expressions are reported with their line and column and make the
+//! program exit with a non-zero status after every expression is evaluated.
+
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+use add_two_numbers::calculator;
+use clap::Parser;
+use serde_json::json;
+
+#[derive(Debug, Parser)]
+#[clap(about = "Adds arbitrarily long numbers, one expression such as `123 + 456` per line")]
+struct Args {
+  /// Radix of the numbers, between 2 and 36. Digits above 9 are written as letters.
+  #[clap(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(2..=36))]
+  radix: u32,
+
+  /// Prints one JSON object per expression instead of plain text.
+  #[clap(long)]
+  json: bool,
+
+  /// Expressions to evaluate. They are read from stdin when none are given.
+  expressions: Vec<String>,
+}
 
-pub fn add_two_numbers(mut left: Vec<i32>, mut right: Vec<i32>) -> Vec<i32> {
-  if left.is_empty() || right.is_empty() {
-    return vec![];
-  }
+fn main() -> ExitCode {
+  let args = Args::parse();
 
-  left.reverse();
-  right.reverse();
+  let from_stdin = args.expressions.is_empty();
 
-  let mut result = LinkedList::new();
+  let lines: Box<dyn Iterator<Item = io::Result<String>>> = if from_stdin {
+    Box::new(io::stdin().lock().lines())
+  } else {
+    Box::new(args.expressions.clone().into_iter().map(Ok))
+  };
 
-  let mut carry = 0;
+  let mut failed = false;
 
-  for i in 0..std::cmp::max(left.len(), right.len()) {
-    let left_digit = if left.len() > i { left[i] } else { 0 };
+  for (i, line) in lines.enumerate() {
+    let line_number = i + 1;
 
-    let right_digit = if right.len() > i { right[i] } else { 0 };
+    let line = match line {
+      Ok(line) => line,
+      Err(error) => {
+        eprintln!("error: line {}: {}", line_number, error);
+        return ExitCode::FAILURE;
+      }
+    };
 
-    if !(0..=9).contains(&left_digit) || !(0..=9).contains(&right_digit) {
-      panic!("numbers must be between 0 and 9");
+    if from_stdin && line.trim().is_empty() {
+      continue;
     }
 
-    let sum = left_digit + right_digit + carry;
-
-    result.push_front(sum % 10);
-
-    carry = sum / 10;
-  }
-
-  if carry > 0 {
-    result.push_front(carry);
-  }
-
-  result.into_iter().collect()
-}
-
-fn main() {
-  println!("Hello, world!");
-}
-
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use proptest::prelude::*;
-
-  #[test]
-  fn empty_lists_should_return_empty_list() {
-    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![]));
-    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![1]));
-    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![1], vec![]));
-    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![], vec![1, 2, 3]));
-    assert_eq!(Vec::<i32>::new(), add_two_numbers(vec![1, 2, 3], vec![]));
-  }
-
-  #[test]
-  fn should_add_numbers_with_a_single_digit() {
-    assert_eq!(vec![3], add_two_numbers(vec![1], vec![2]));
-    assert_eq!(vec![1, 1], add_two_numbers(vec![9], vec![2]));
-  }
-
-  #[test]
-  fn should_add_numbers_with_many_digits() {
-    assert_eq!(vec![5, 5], add_two_numbers(vec![2, 2], vec![3, 3]));
-    assert_eq!(vec![5, 2], add_two_numbers(vec![2, 9], vec![2, 3]));
-    assert_eq!(vec![4, 7, 6], add_two_numbers(vec![2, 9, 3], vec![1, 8, 3]));
-    assert_eq!(vec![4, 4, 7], add_two_numbers(vec![1, 7, 9], vec![2, 6, 8]));
-    assert_eq!(
-      vec![3, 7, 3, 3, 2],
-      add_two_numbers(vec![1, 9, 1, 7, 1], vec![1, 8, 1, 6, 1])
-    );
-  }
-
-  #[test]
-  fn should_add_numbers_with_digits_of_different_lengths() {
-    assert_eq!(vec![2, 5], add_two_numbers(vec![2, 2], vec![3]));
-    assert_eq!(vec![2, 5], add_two_numbers(vec![3], vec![2, 2]));
-    assert_eq!(vec![3, 1], add_two_numbers(vec![2, 2], vec![9]));
-    assert_eq!(vec![3, 1], add_two_numbers(vec![9], vec![2, 2]));
-  }
-
-  prop_compose! {
-    fn list_contains_negative_number()(mut xs: Vec<i32>) -> Vec<i32> {
-      if !xs.is_empty() {
-        let i = rand::thread_rng().gen_range(0..xs.len());
-
-        if xs[i] > 0 {
-          xs[i] = -xs[i];
+    match calculator::evaluate(&line, args.radix) {
+      Ok(sum) => {
+        let sum = calculator::format_digits(&sum, args.radix);
+
+        if args.json {
+          println!(
+            "{}",
+            json!({ "line": line_number, "expression": line, "sum": sum })
+          );
+        } else {
+          println!("{}", sum);
+        }
+      }
+      Err(error) => {
+        failed = true;
+
+        if args.json {
+          println!(
+            "{}",
+            json!({
+              "line": line_number,
+              "expression": line,
+              "error": { "column": error.column, "message": error.kind.to_string() },
+            })
+          );
+        } else {
+          eprintln!(
+            "error: line {}, column {}: {}",
+            line_number, error.column, error.kind
+          );
         }
       }
-
-      xs
     }
   }
 
-  proptest! {
-    #[test]
-    #[should_panic(expected = "numbers must be between 0 and 9")]
-    fn numbers_must_be_between_0_and_9(left in list_contains_negative_number(), right in list_contains_negative_number()) {
-      prop_assume!(!left.is_empty() && !right.is_empty());
-      add_two_numbers(left, right);
-    }
+  if failed {
+    ExitCode::FAILURE
+  } else {
+    ExitCode::SUCCESS
   }
 }
@@ -0,0 +1,170 @@
+//! Digit lists in bases other than 10.
+//!
+//! Digits are always stored most significant digit first, the same way
+//! `add_two_numbers` stores them, and every digit must be smaller than the
+//! radix.
+
+pub const MIN_RADIX: u32 = 2;
+pub const MAX_RADIX: u32 = 36;
+
+pub(crate) fn assert_radix_is_valid(radix: u32) {
+  if !(MIN_RADIX..=MAX_RADIX).contains(&radix) {
+    panic!(
+      "radix must be between {} and {}. got {}",
+      MIN_RADIX, MAX_RADIX, radix
+    );
+  }
+}
+
+/// Re-encodes `digits` from base `from` to base `to`.
+///
+/// The number is divided by `to` over and over again, the remainders are the
+/// digits of the result. Only one digit list is kept in memory at a time, so
+/// numbers of any length can be converted.
+///
+/// An empty list stays empty and leading zeros are not kept.
+///
+/// # Panics
+///
+/// Panics if a radix is not between 2 and 36 or if a digit is not between 0
+/// and `from - 1`.
+pub fn convert_radix(digits: &[i32], from: u32, to: u32) -> Vec<i32> {
+  assert_radix_is_valid(from);
+  assert_radix_is_valid(to);
+
+  let (from, to) = (from as i64, to as i64);
+
+  if digits
+    .iter()
+    .any(|digit| !(0..from).contains(&(*digit as i64)))
+  {
+    panic!("numbers must be between 0 and {}", from - 1);
+  }
+
+  if digits.is_empty() {
+    return vec![];
+  }
+
+  let mut number: Vec<i64> = digits
+    .iter()
+    .map(|digit| *digit as i64)
+    .skip_while(|digit| *digit == 0)
+    .collect();
+
+  if number.is_empty() {
+    return vec![0];
+  }
+
+  let mut result = vec![];
+
+  while !number.is_empty() {
+    let mut quotient = Vec::with_capacity(number.len());
+    let mut remainder = 0;
+
+    for digit in number {
+      let current = remainder * from + digit;
+
+      // Leading zeros are dropped so the number shrinks on every iteration.
+      if !quotient.is_empty() || current / to > 0 {
+        quotient.push(current / to);
+      }
+
+      remainder = current % to;
+    }
+
+    result.push(remainder as i32);
+
+    number = quotient;
+  }
+
+  result.reverse();
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  fn to_u64(digits: &[i32], radix: u32) -> u64 {
+    digits
+      .iter()
+      .fold(0, |n, digit| n * radix as u64 + *digit as u64)
+  }
+
+  #[test]
+  fn converts_between_common_bases() {
+    assert_eq!(
+      vec![1, 1, 1, 1, 1, 1, 1, 1],
+      convert_radix(&[2, 5, 5], 10, 2)
+    );
+    assert_eq!(vec![15, 15], convert_radix(&[2, 5, 5], 10, 16));
+    assert_eq!(vec![2, 5, 5], convert_radix(&[15, 15], 16, 10));
+    assert_eq!(vec![1, 0, 0, 0, 0], convert_radix(&[6, 5, 5, 3, 6], 10, 16));
+    assert_eq!(vec![1, 35], convert_radix(&[7, 1], 10, 36));
+  }
+
+  #[test]
+  fn zero_and_empty_lists() {
+    assert_eq!(Vec::<i32>::new(), convert_radix(&[], 10, 2));
+    assert_eq!(vec![0], convert_radix(&[0], 10, 2));
+    assert_eq!(vec![0], convert_radix(&[0, 0, 0], 16, 10));
+    assert_eq!(vec![1, 0], convert_radix(&[0, 0, 1, 0], 2, 2));
+  }
+
+  #[test]
+  fn converts_numbers_that_do_not_fit_in_u128() {
+    let digits = vec![1; 200];
+
+    let hexadecimal = convert_radix(&digits, 2, 16);
+
+    assert_eq!(50, hexadecimal.len());
+    assert!(hexadecimal.iter().all(|digit| *digit == 15));
+    assert_eq!(digits, convert_radix(&hexadecimal, 16, 2));
+  }
+
+  #[test]
+  #[should_panic(expected = "numbers must be between 0 and 1")]
+  fn digits_must_be_smaller_than_the_radix() {
+    convert_radix(&[1, 2], 2, 10);
+  }
+
+  #[test]
+  #[should_panic(expected = "radix must be between 2 and 36. got 37")]
+  fn radix_must_be_between_2_and_36() {
+    convert_radix(&[1], 37, 10);
+  }
+
+  fn radix() -> impl Strategy<Value = u32> {
+    MIN_RADIX..=MAX_RADIX
+  }
+
+  proptest! {
+    #[test]
+    fn preserves_the_value(n: u32, from in radix(), to in radix()) {
+      let decimal: Vec<i32> = n
+        .to_string()
+        .chars()
+        .map(|c| c.to_digit(10).unwrap() as i32)
+        .collect();
+
+      let digits = convert_radix(&convert_radix(&decimal, 10, from), from, to);
+
+      prop_assert_eq!(n as u64, to_u64(&digits, to));
+    }
+
+    #[test]
+    fn converting_back_returns_the_original_digits(
+      digits in proptest::collection::vec(0..16, 1..100),
+      to in radix()
+    ) {
+      let expected: Vec<i32> = match digits.iter().position(|digit| *digit != 0) {
+        None => vec![0],
+        Some(i) => digits[i..].to_vec(),
+      };
+
+      prop_assert_eq!(expected, convert_radix(&convert_radix(&digits, 16, to), to, 16));
+    }
+  }
+}
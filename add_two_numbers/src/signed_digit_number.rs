@@ -0,0 +1,350 @@
+//! A signed arbitrary-precision number stored as a sign plus the digits of
+//! its magnitude.
+//!
+//! Zero has a single representation: a positive sign and a magnitude of `[0]`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::digit_number::{DigitNumber, ParseDigitNumberError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sign {
+  Negative,
+  Positive,
+}
+
+impl Neg for Sign {
+  type Output = Sign;
+
+  fn neg(self) -> Sign {
+    match self {
+      Sign::Negative => Sign::Positive,
+      Sign::Positive => Sign::Negative,
+    }
+  }
+}
+
+impl Mul for Sign {
+  type Output = Sign;
+
+  fn mul(self, other: Sign) -> Sign {
+    if self == other {
+      Sign::Positive
+    } else {
+      Sign::Negative
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SignedDigitNumber {
+  sign: Sign,
+  magnitude: DigitNumber,
+}
+
+impl SignedDigitNumber {
+  pub fn zero() -> Self {
+    Self::new(Sign::Positive, DigitNumber::zero())
+  }
+
+  /// A negative zero becomes a positive zero.
+  pub fn new(sign: Sign, magnitude: DigitNumber) -> Self {
+    let sign = if magnitude.is_zero() {
+      Sign::Positive
+    } else {
+      sign
+    };
+
+    Self { sign, magnitude }
+  }
+
+  /// Builds a number from the digits of its magnitude, most significant digit
+  /// first. Leading zeros are removed.
+  ///
+  /// # Panics
+  ///
+  /// Panics if any digit is not between 0 and 9.
+  pub fn from_digits(sign: Sign, digits: Vec<i32>) -> Self {
+    Self::new(sign, DigitNumber::from_digits(digits))
+  }
+
+  pub fn sign(&self) -> Sign {
+    self.sign
+  }
+
+  pub fn magnitude(&self) -> &DigitNumber {
+    &self.magnitude
+  }
+
+  pub fn is_zero(&self) -> bool {
+    self.magnitude.is_zero()
+  }
+
+  pub fn is_negative(&self) -> bool {
+    self.sign == Sign::Negative
+  }
+
+  pub fn abs(&self) -> Self {
+    Self::new(Sign::Positive, self.magnitude.clone())
+  }
+}
+
+impl Default for SignedDigitNumber {
+  fn default() -> Self {
+    Self::zero()
+  }
+}
+
+impl From<DigitNumber> for SignedDigitNumber {
+  fn from(magnitude: DigitNumber) -> Self {
+    Self::new(Sign::Positive, magnitude)
+  }
+}
+
+impl From<i64> for SignedDigitNumber {
+  fn from(n: i64) -> Self {
+    let sign = if n < 0 {
+      Sign::Negative
+    } else {
+      Sign::Positive
+    };
+
+    Self::new(sign, DigitNumber::from(n.unsigned_abs()))
+  }
+}
+
+impl Ord for SignedDigitNumber {
+  fn cmp(&self, other: &Self) -> Ordering {
+    match (self.sign, other.sign) {
+      (Sign::Negative, Sign::Positive) => Ordering::Less,
+      (Sign::Positive, Sign::Negative) => Ordering::Greater,
+      (Sign::Positive, Sign::Positive) => self.magnitude.cmp(&other.magnitude),
+      // The bigger the magnitude, the smaller the negative number.
+      (Sign::Negative, Sign::Negative) => other.magnitude.cmp(&self.magnitude),
+    }
+  }
+}
+
+impl PartialOrd for SignedDigitNumber {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl FromStr for SignedDigitNumber {
+  type Err = ParseDigitNumberError;
+
+  /// Accepts an optional `-` or `+` followed by decimal digits.
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (sign, digits) = match s.strip_prefix('-') {
+      Some(digits) => (Sign::Negative, digits),
+      None => (Sign::Positive, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    let sign_len = s.len() - digits.len();
+
+    let magnitude = digits.parse::<DigitNumber>().map_err(|error| match error {
+      ParseDigitNumberError::InvalidDigit { index, character } => {
+        ParseDigitNumberError::InvalidDigit {
+          index: index + sign_len,
+          character,
+        }
+      }
+      error => error,
+    })?;
+
+    Ok(Self::new(sign, magnitude))
+  }
+}
+
+impl fmt::Display for SignedDigitNumber {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.is_negative() {
+      write!(f, "-")?;
+    }
+
+    write!(f, "{}", self.magnitude)
+  }
+}
+
+impl Neg for &SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn neg(self) -> SignedDigitNumber {
+    SignedDigitNumber::new(-self.sign, self.magnitude.clone())
+  }
+}
+
+impl Neg for SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn neg(self) -> SignedDigitNumber {
+    SignedDigitNumber::new(-self.sign, self.magnitude)
+  }
+}
+
+impl Add for &SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn add(self, other: &SignedDigitNumber) -> SignedDigitNumber {
+    if self.sign == other.sign {
+      return SignedDigitNumber::new(self.sign, &self.magnitude + &other.magnitude);
+    }
+
+    // The signs are different, so the result has the sign of the operand
+    // with the bigger magnitude and the smaller magnitude is subtracted
+    // from the bigger one.
+    match self.magnitude.cmp(&other.magnitude) {
+      Ordering::Equal => SignedDigitNumber::zero(),
+      Ordering::Greater => SignedDigitNumber::new(self.sign, &self.magnitude - &other.magnitude),
+      Ordering::Less => SignedDigitNumber::new(other.sign, &other.magnitude - &self.magnitude),
+    }
+  }
+}
+
+impl Sub for &SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn sub(self, other: &SignedDigitNumber) -> SignedDigitNumber {
+    self + &(-other)
+  }
+}
+
+impl Mul for &SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn mul(self, other: &SignedDigitNumber) -> SignedDigitNumber {
+    SignedDigitNumber::new(self.sign * other.sign, &self.magnitude * &other.magnitude)
+  }
+}
+
+impl Add for SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn add(self, other: SignedDigitNumber) -> SignedDigitNumber {
+    &self + &other
+  }
+}
+
+impl Sub for SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn sub(self, other: SignedDigitNumber) -> SignedDigitNumber {
+    &self - &other
+  }
+}
+
+impl Mul for SignedDigitNumber {
+  type Output = SignedDigitNumber;
+
+  fn mul(self, other: SignedDigitNumber) -> SignedDigitNumber {
+    &self * &other
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  fn number(s: &str) -> SignedDigitNumber {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn zero_is_always_positive() {
+    assert_eq!(Sign::Positive, number("-0").sign());
+    assert_eq!(Sign::Positive, number("-000").sign());
+    assert_eq!(
+      SignedDigitNumber::zero(),
+      SignedDigitNumber::from_digits(Sign::Negative, vec![0, 0])
+    );
+    assert_eq!(SignedDigitNumber::zero(), -SignedDigitNumber::zero());
+    assert_eq!(SignedDigitNumber::zero(), number("-5") + number("5"));
+    assert_eq!("0", (number("-5") * number("0")).to_string());
+  }
+
+  #[test]
+  fn leading_zeros_are_removed() {
+    assert_eq!(
+      vec![1, 2],
+      SignedDigitNumber::from_digits(Sign::Negative, vec![0, 0, 1, 2])
+        .magnitude()
+        .digits()
+    );
+    assert_eq!("-12", number("-0012").to_string());
+    assert_eq!("12", number("+0012").to_string());
+  }
+
+  #[test]
+  fn parse_errors() {
+    assert_eq!(
+      Err(ParseDigitNumberError::Empty),
+      "".parse::<SignedDigitNumber>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::Empty),
+      "-".parse::<SignedDigitNumber>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::InvalidDigit {
+        index: 3,
+        character: 'x'
+      }),
+      "-12x".parse::<SignedDigitNumber>()
+    );
+    assert_eq!(
+      Err(ParseDigitNumberError::InvalidDigit {
+        index: 1,
+        character: '-'
+      }),
+      "--1".parse::<SignedDigitNumber>()
+    );
+  }
+
+  #[test]
+  fn adding_numbers_with_different_signs() {
+    assert_eq!(number("-1"), number("99") + number("-100"));
+    assert_eq!(number("1"), number("-99") + number("100"));
+    assert_eq!(number("-199"), number("-99") + number("-100"));
+    assert_eq!(number("9"), number("10") + number("-1"));
+  }
+
+  #[test]
+  fn subtraction() {
+    assert_eq!(number("-1"), number("0") - number("1"));
+    assert_eq!(number("1000"), number("999") - number("-1"));
+    assert_eq!(number("-1000"), number("-999") - number("1"));
+  }
+
+  #[test]
+  fn ordering() {
+    assert!(number("-10") < number("-9"));
+    assert!(number("-1") < number("0"));
+    assert!(number("0") < number("1"));
+    assert!(number("-100") < number("1"));
+  }
+
+  proptest! {
+    #[test]
+    fn matches_i64_arithmetic(left in -(1_i64 << 31)..(1 << 31), right in -(1_i64 << 31)..(1 << 31)) {
+      let (l, r) = (SignedDigitNumber::from(left), SignedDigitNumber::from(right));
+
+      prop_assert_eq!(SignedDigitNumber::from(left + right), &l + &r);
+      prop_assert_eq!(SignedDigitNumber::from(left - right), &l - &r);
+      prop_assert_eq!(SignedDigitNumber::from(left * right), &l * &r);
+      prop_assert_eq!(left.cmp(&right), l.cmp(&r));
+      prop_assert_eq!(left.to_string(), l.to_string());
+    }
+
+    #[test]
+    fn display_and_from_str_roundtrip(n: i64) {
+      let number = SignedDigitNumber::from(n);
+
+      prop_assert_eq!(number.clone(), number.to_string().parse::<SignedDigitNumber>().unwrap());
+    }
+  }
+}
@@ -0,0 +1,293 @@
+//! Addition without materializing the whole numbers in memory.
+//!
+//! [`sum_digits`] works on digits that are produced least significant digit
+//! first (little-endian), which is the order the carry moves in, so the sum
+//! can be yielded one digit at a time without allocating.
+//!
+//! [`PackedDecimal`] stores several decimal digits in a single `u64` limb, so
+//! one machine addition handles up to 18 digits at a time.
+
+use std::iter::Fuse;
+use std::ops::Add;
+
+/// Adds two numbers given as little-endian digit iterators and lazily yields
+/// the digits of the sum, also least significant digit first.
+///
+/// The operands may have different lengths.
+///
+/// # Panics
+///
+/// The iterator panics when it reaches a digit that is not between 0 and 9.
+pub fn sum_digits<L, R>(left: L, right: R) -> SumDigits<L::IntoIter, R::IntoIter>
+where
+  L: IntoIterator<Item = i32>,
+  R: IntoIterator<Item = i32>,
+{
+  SumDigits {
+    left: left.into_iter().fuse(),
+    right: right.into_iter().fuse(),
+    carry: 0,
+  }
+}
+
+/// Iterator returned by [`sum_digits`].
+#[derive(Debug, Clone)]
+pub struct SumDigits<L, R> {
+  left: Fuse<L>,
+  right: Fuse<R>,
+  carry: i32,
+}
+
+impl<L, R> Iterator for SumDigits<L, R>
+where
+  L: Iterator<Item = i32>,
+  R: Iterator<Item = i32>,
+{
+  type Item = i32;
+
+  fn next(&mut self) -> Option<i32> {
+    let (left_digit, right_digit) = match (self.left.next(), self.right.next()) {
+      (None, None) if self.carry == 0 => return None,
+      (left_digit, right_digit) => (left_digit.unwrap_or(0), right_digit.unwrap_or(0)),
+    };
+
+    if !(0..=9).contains(&left_digit) || !(0..=9).contains(&right_digit) {
+      panic!("numbers must be between 0 and 9");
+    }
+
+    let sum = left_digit + right_digit + self.carry;
+
+    self.carry = sum / 10;
+
+    Some(sum % 10)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (left_min, left_max) = self.left.size_hint();
+    let (right_min, right_max) = self.right.size_hint();
+
+    let carry = self.carry as usize;
+
+    let min = std::cmp::max(std::cmp::max(left_min, right_min), carry);
+
+    // The final carry may add one more digit.
+    let max = left_max
+      .zip(right_max)
+      .and_then(|(left_max, right_max)| std::cmp::max(left_max, right_max).checked_add(1));
+
+    (min, max)
+  }
+}
+
+/// A non-negative decimal number stored as little-endian limbs of
+/// `DIGITS_PER_LIMB` decimal digits each, between 9 and 18.
+///
+/// Two limbs of 18 digits plus a carry still fit in a `u64`, so adding two
+/// numbers takes one machine addition per limb instead of one per digit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedDecimal<const DIGITS_PER_LIMB: u32> {
+  /// Least significant limb first, without leading zero limbs.
+  /// Zero is represented as an empty list.
+  limbs: Vec<u64>,
+}
+
+pub type PackedDecimal9 = PackedDecimal<9>;
+pub type PackedDecimal18 = PackedDecimal<18>;
+
+impl<const DIGITS_PER_LIMB: u32> PackedDecimal<DIGITS_PER_LIMB> {
+  const LIMB_DIGITS: usize = {
+    assert!(
+      9 <= DIGITS_PER_LIMB && DIGITS_PER_LIMB <= 18,
+      "a limb must hold between 9 and 18 digits"
+    );
+
+    DIGITS_PER_LIMB as usize
+  };
+
+  const BASE: u64 = 10_u64.pow(DIGITS_PER_LIMB);
+
+  /// Packs digits given most significant digit first.
+  ///
+  /// # Panics
+  ///
+  /// Panics if any digit is not between 0 and 9.
+  pub fn from_digits(digits: &[i32]) -> Self {
+    let mut limbs = Vec::with_capacity(digits.len() / Self::LIMB_DIGITS + 1);
+
+    for chunk in digits.rchunks(Self::LIMB_DIGITS) {
+      let limb = chunk.iter().fold(0, |limb, digit| {
+        if !(0..=9).contains(digit) {
+          panic!("numbers must be between 0 and 9");
+        }
+
+        limb * 10 + *digit as u64
+      });
+
+      limbs.push(limb);
+    }
+
+    Self::from_limbs(limbs)
+  }
+
+  /// Unpacks the digits, most significant digit first.
+  /// Zero is returned as `[0]`.
+  pub fn to_digits(&self) -> Vec<i32> {
+    let mut digits = Vec::with_capacity(self.limbs.len() * Self::LIMB_DIGITS);
+
+    for mut limb in self.limbs.iter().copied() {
+      for _ in 0..Self::LIMB_DIGITS {
+        digits.push((limb % 10) as i32);
+        limb /= 10;
+      }
+    }
+
+    while digits.len() > 1 && digits.last() == Some(&0) {
+      digits.pop();
+    }
+
+    if digits.is_empty() {
+      digits.push(0);
+    }
+
+    digits.reverse();
+
+    digits
+  }
+
+  pub fn limbs(&self) -> &[u64] {
+    &self.limbs
+  }
+
+  fn from_limbs(mut limbs: Vec<u64>) -> Self {
+    while limbs.last() == Some(&0) {
+      limbs.pop();
+    }
+
+    Self { limbs }
+  }
+}
+
+impl<const DIGITS_PER_LIMB: u32> Add for &PackedDecimal<DIGITS_PER_LIMB> {
+  type Output = PackedDecimal<DIGITS_PER_LIMB>;
+
+  fn add(self, other: &PackedDecimal<DIGITS_PER_LIMB>) -> PackedDecimal<DIGITS_PER_LIMB> {
+    let mut limbs = Vec::with_capacity(std::cmp::max(self.limbs.len(), other.limbs.len()) + 1);
+
+    let mut carry = 0;
+
+    for i in 0..std::cmp::max(self.limbs.len(), other.limbs.len()) {
+      let sum = self.limbs.get(i).unwrap_or(&0) + other.limbs.get(i).unwrap_or(&0) + carry;
+
+      limbs.push(sum % PackedDecimal::<DIGITS_PER_LIMB>::BASE);
+
+      carry = sum / PackedDecimal::<DIGITS_PER_LIMB>::BASE;
+    }
+
+    if carry > 0 {
+      limbs.push(carry);
+    }
+
+    PackedDecimal::from_limbs(limbs)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::add_two_numbers;
+  use proptest::prelude::*;
+
+  fn streaming_add(left: &[i32], right: &[i32]) -> Vec<i32> {
+    let mut sum: Vec<i32> =
+      sum_digits(left.iter().rev().copied(), right.iter().rev().copied()).collect();
+    sum.reverse();
+    sum
+  }
+
+  #[test]
+  fn yields_the_sum_least_significant_digit_first() {
+    assert_eq!(
+      vec![1, 0, 0, 1],
+      sum_digits(vec![9, 9, 9], vec![2]).collect::<Vec<_>>()
+    );
+    assert_eq!(vec![3], sum_digits(vec![1], vec![2]).collect::<Vec<_>>());
+    assert_eq!(
+      Vec::<i32>::new(),
+      sum_digits(vec![], vec![]).collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn is_lazy() {
+    let mut sum = sum_digits(std::iter::repeat(9), std::iter::once(1));
+
+    assert_eq!(Some(0), sum.next());
+    assert_eq!(Some(0), sum.next());
+    assert_eq!(Some(0), sum.next());
+  }
+
+  #[test]
+  #[should_panic(expected = "numbers must be between 0 and 9")]
+  fn digits_must_be_between_0_and_9() {
+    sum_digits(vec![1], vec![10]).for_each(drop);
+  }
+
+  #[test]
+  fn packs_digits_in_limbs() {
+    let n = PackedDecimal9::from_digits(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1]);
+
+    assert_eq!(&[345_678_901, 12], n.limbs());
+    assert_eq!(vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1], n.to_digits());
+    assert_eq!(vec![0], PackedDecimal18::from_digits(&[0, 0]).to_digits());
+    assert_eq!(
+      vec![1, 0],
+      PackedDecimal18::from_digits(&[0, 1, 0]).to_digits()
+    );
+  }
+
+  #[test]
+  fn carries_between_limbs() {
+    let left = PackedDecimal9::from_digits(&[9; 18]);
+    let right = PackedDecimal9::from_digits(&[1]);
+
+    let mut expected = vec![1];
+    expected.extend(vec![0; 18]);
+
+    assert_eq!(expected, (&left + &right).to_digits());
+  }
+
+  fn digits() -> impl Strategy<Value = Vec<i32>> {
+    proptest::collection::vec(0..=9, 1..100)
+  }
+
+  proptest! {
+    #[test]
+    fn streaming_matches_add_two_numbers(left in digits(), right in digits()) {
+      prop_assert_eq!(add_two_numbers(left.clone(), right.clone()), streaming_add(&left, &right));
+    }
+
+    #[test]
+    fn size_hint_is_correct(left in digits(), right in digits()) {
+      let sum = sum_digits(left.clone(), right.clone());
+      let (min, max) = sum.size_hint();
+      let len = sum.count();
+
+      prop_assert!(min <= len);
+      prop_assert!(len <= max.unwrap());
+    }
+
+    #[test]
+    fn packed_matches_add_two_numbers(left in digits(), right in digits()) {
+      let expected = crate::DigitNumber::from_digits(add_two_numbers(left.clone(), right.clone())).into_digits();
+
+      prop_assert_eq!(
+        &expected,
+        &(&PackedDecimal9::from_digits(&left) + &PackedDecimal9::from_digits(&right)).to_digits()
+      );
+      prop_assert_eq!(
+        &expected,
+        &(&PackedDecimal18::from_digits(&left) + &PackedDecimal18::from_digits(&right)).to_digits()
+      );
+    }
+  }
+}
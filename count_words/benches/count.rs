@@ -0,0 +1,48 @@
+use std::num::NonZeroUsize;
+
+use count_words::{count_words, count_words_parallel};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::Rng;
+
+const WORDS: [&str; 8] = [
+  "GET", "POST", "/users", "status", "error", "timeout", "server", "200",
+];
+
+/// Text that looks like a log file, one request per line.
+fn random_log(len: usize) -> String {
+  let mut rng = rand::thread_rng();
+
+  let mut log = String::with_capacity(len + 16);
+
+  while log.len() < len {
+    log.push_str(WORDS[rng.gen_range(0..WORDS.len())]);
+    log.push(if rng.gen_ratio(1, 8) { '\n' } else { ' ' });
+  }
+
+  log
+}
+
+fn count(c: &mut Criterion) {
+  let mut group = c.benchmark_group("count_words");
+
+  let log = random_log(16 * 1024 * 1024);
+
+  group.throughput(Throughput::Bytes(log.len() as u64));
+
+  group.bench_with_input(BenchmarkId::new("sequential", 1), &log, |b, log| {
+    b.iter(|| count_words(black_box(log)))
+  });
+
+  for threads in [1, 2, 4, 8, 16] {
+    let threads = NonZeroUsize::new(threads).unwrap();
+
+    group.bench_with_input(BenchmarkId::new("parallel", threads), &log, |b, log| {
+      b.iter(|| count_words_parallel(black_box(log), threads))
+    });
+  }
+
+  group.finish();
+}
+
+criterion_group!(benches, count);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+pub mod parallel;
+pub mod report;
+pub mod streaming;
+pub mod word_counter;
+
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+
+pub use word_counter::{Segmentation, WordCounter};
+
+/// Given a sentence, the program should count the number of words that end
+/// with either an "s" or an "r". A word ends when a non-letter appears. The
+/// program returns the number of words.
+///
+/// Use [`WordCounter`] to count words with other rules.
+pub fn count_words(s: &str) -> usize {
+  WordCounter::default().count(s)
+}
+
+/// Same as [`count_words`] but reads the sentence from `reader` in chunks,
+/// so the whole input never has to be in memory.
+pub fn count_words_from_reader<R: Read>(reader: R) -> io::Result<usize> {
+  WordCounter::default().count_reader(reader)
+}
+
+/// Same as [`count_words`] but splits the sentence between `threads` threads.
+pub fn count_words_parallel(s: &str, threads: NonZeroUsize) -> usize {
+  WordCounter::default().count_parallel(s, threads)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn no_matches() {
+    assert_eq!(0, count_words(""));
+    assert_eq!(0, count_words("hello"));
+    assert_eq!(0, count_words("helasarlo"));
+    assert_eq!(0, count_words("hello world"));
+    assert_eq!(0, count_words("hellaso arworld"));
+    assert_eq!(0, count_words("ra sa"));
+  }
+
+  #[test]
+  fn words_ending_in_s() {
+    assert_eq!(1, count_words("s"));
+    assert_eq!(1, count_words("xs"));
+    assert_eq!(2, count_words("s s"));
+    assert_eq!(2, count_words("xs xs"));
+    assert_eq!(2, count_words("saasaa xxxs aaaaa zzzzs"));
+  }
+
+  #[test]
+  fn words_ending_in_r() {
+    assert_eq!(1, count_words("r"));
+    assert_eq!(1, count_words("xr"));
+    assert_eq!(2, count_words("r r"));
+    assert_eq!(2, count_words("xr xr"));
+    assert_eq!(1, count_words("saasaa xxxr aaaaa"));
+    assert_eq!(2, count_words("saasaa xxxr aaaaa zzzzr"));
+  }
+
+  #[test]
+  fn from_reader() {
+    assert_eq!(0, count_words_from_reader("".as_bytes()).unwrap());
+    assert_eq!(
+      2,
+      count_words_from_reader(io::BufReader::new("saasaa xxxr aaaaa zzzzs".as_bytes())).unwrap()
+    );
+  }
+}
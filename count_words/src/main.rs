@@ -1,52 +1,164 @@
-/// Given a sentence, the program should count the number of words that end
-/// with either an "s" or an "r". A word ends when a non-letter appears. The
-/// program returns the number of words.
-pub fn count_words(s: &str) -> usize {
-  let mut count = 0;
-
-  for word in s.split_terminator(|character: char| !character.is_alphabetic()) {
-    if word.ends_with('s') || word.ends_with('r') {
-      count += 1;
-    }
-  }
+//! Counts the words of files, like `wc -w`, that match a set of suffixes.
+//!
+//! Files are given as paths or glob patterns such as `docs/**/*.md`. Text is
+//! read from stdin when no files are given. The count of every file and the
+//! total are printed as a table or, with `--json`, as a JSON object.
+//!
+//! Files that can't be read are reported and make the program exit with a
+//! non-zero status after every other file is counted.
+
+use std::fs::File;
+use std::io;
+use std::process::ExitCode;
+
+use clap::Parser;
+use count_words::{Segmentation, WordCounter};
+use serde_json::json;
+
+#[derive(Debug, Parser)]
+#[clap(about = "Counts the words that end with one of the suffixes, like `wc -w`")]
+struct Args {
+  /// Suffix that counted words end with. Can be repeated. Defaults to "s" and "r".
+  #[clap(long = "suffix", short = 's')]
+  suffixes: Vec<String>,
+
+  /// Matches suffixes regardless of case.
+  #[clap(long, short = 'i')]
+  case_insensitive: bool,
+
+  /// Finds words with the Unicode word boundary rules instead of splitting
+  /// on every character that is not a letter.
+  #[clap(long)]
+  unicode: bool,
 
-  count
+  /// Prints a JSON object instead of a table.
+  #[clap(long)]
+  json: bool,
+
+  /// Files or glob patterns. Text is read from stdin when none are given.
+  files: Vec<String>,
 }
 
-fn main() {
-  println!("Hello, world!");
+/// The count of a single input or why it couldn't be counted.
+struct Count {
+  path: String,
+  result: Result<usize, String>,
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-
-  #[test]
-  fn no_matches() {
-    assert_eq!(0, count_words(""));
-    assert_eq!(0, count_words("hello"));
-    assert_eq!(0, count_words("helasarlo"));
-    assert_eq!(0, count_words("hello world"));
-    assert_eq!(0, count_words("hellaso arworld"));
-    assert_eq!(0, count_words("ra sa"));
+fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let suffixes = if args.suffixes.is_empty() {
+    vec!["s".to_string(), "r".to_string()]
+  } else {
+    args.suffixes.clone()
+  };
+
+  let counter = WordCounter::builder()
+    .suffixes(suffixes)
+    .case_insensitive(args.case_insensitive)
+    .segmentation(if args.unicode {
+      Segmentation::Unicode
+    } else {
+      Segmentation::Boundary
+    })
+    .build();
+
+  let counts = if args.files.is_empty() {
+    vec![Count {
+      path: "-".to_string(),
+      result: counter
+        .count_reader(io::stdin().lock())
+        .map_err(|error| error.to_string()),
+    }]
+  } else {
+    args
+      .files
+      .iter()
+      .flat_map(|pattern| expand(pattern))
+      .map(|path| match path {
+        Err(count) => count,
+        Ok(path) => Count {
+          result: File::open(&path)
+            .and_then(|file| counter.count_reader(file))
+            .map_err(|error| error.to_string()),
+          path,
+        },
+      })
+      .collect()
+  };
+
+  let total: usize = counts
+    .iter()
+    .filter_map(|count| count.result.clone().ok())
+    .sum();
+
+  if args.json {
+    print_json(&counts, total);
+  } else {
+    print_table(&counts, total);
   }
 
-  #[test]
-  fn words_ending_in_s() {
-    assert_eq!(1, count_words("s"));
-    assert_eq!(1, count_words("xs"));
-    assert_eq!(2, count_words("s s"));
-    assert_eq!(2, count_words("xs xs"));
-    assert_eq!(2, count_words("saasaa xxxs aaaaa zzzzs"));
+  if counts.iter().any(|count| count.result.is_err()) {
+    ExitCode::FAILURE
+  } else {
+    ExitCode::SUCCESS
   }
+}
 
-  #[test]
-  fn words_ending_in_r() {
-    assert_eq!(1, count_words("r"));
-    assert_eq!(1, count_words("xr"));
-    assert_eq!(2, count_words("r r"));
-    assert_eq!(2, count_words("xr xr"));
-    assert_eq!(1, count_words("saasaa xxxr aaaaa"));
-    assert_eq!(2, count_words("saasaa xxxr aaaaa zzzzr"));
+/// Returns the paths that match `pattern`. Patterns without glob characters
+/// are returned as they are so missing files are reported when they are opened.
+fn expand(pattern: &str) -> Vec<Result<String, Count>> {
+  let failed = |message: String| Count {
+    path: pattern.to_string(),
+    result: Err(message),
+  };
+
+  if !pattern.contains(['*', '?', '[']) {
+    return vec![Ok(pattern.to_string())];
   }
+
+  match glob::glob(pattern) {
+    Err(error) => vec![Err(failed(error.to_string()))],
+    Ok(paths) => {
+      let paths: Vec<_> = paths
+        .map(|path| match path {
+          Ok(path) => Ok(path.display().to_string()),
+          Err(error) => Err(Count {
+            path: error.path().display().to_string(),
+            result: Err(error.error().to_string()),
+          }),
+        })
+        .collect();
+
+      if paths.is_empty() {
+        vec![Err(failed("no files match the pattern".to_string()))]
+      } else {
+        paths
+      }
+    }
+  }
+}
+
+fn print_table(counts: &[Count], total: usize) {
+  for count in counts {
+    match &count.result {
+      Ok(words) => println!("{:>8} {}", words, count.path),
+      Err(error) => eprintln!("count_words: {}: {}", count.path, error),
+    }
+  }
+
+  println!("{:>8} total", total);
+}
+
+fn print_json(counts: &[Count], total: usize) {
+  let files: Vec<_> = counts
+    .iter()
+    .map(|count| match &count.result {
+      Ok(words) => json!({ "path": count.path, "count": words }),
+      Err(error) => json!({ "path": count.path, "error": error }),
+    })
+    .collect();
+
+  println!("{}", json!({ "files": files, "total": total }));
 }
@@ -0,0 +1,152 @@
+//! Counts words of large inputs on several threads.
+//!
+//! The input is split in chunks of roughly the same size. A chunk always ends
+//! right before a character that separates words, a boundary or whitespace
+//! with [`Segmentation::Unicode`](crate::Segmentation::Unicode), so no word is
+//! split between two chunks,
+//! which means that the sum of the counts of every chunk is exactly the count
+//! of the whole input.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::WordCounter;
+
+impl WordCounter {
+  /// Same as [`WordCounter::count`] but counts the words on up to `threads`
+  /// scoped threads.
+  pub fn count_parallel(&self, s: &str, threads: NonZeroUsize) -> usize {
+    let chunks = self.split_at_boundaries(s, threads.get());
+
+    if chunks.len() == 1 {
+      return self.count(s);
+    }
+
+    thread::scope(|scope| {
+      let handles: Vec<_> = chunks
+        .into_iter()
+        .map(|chunk| scope.spawn(move || self.count(chunk)))
+        .collect();
+
+      handles
+        .into_iter()
+        .map(|handle| handle.join().expect("counting thread panicked"))
+        .sum()
+    })
+  }
+
+  /// Splits `s` in at most `n` chunks. Every chunk but the last one ends right
+  /// before a character that separates words.
+  fn split_at_boundaries<'a>(&self, s: &'a str, n: usize) -> Vec<&'a str> {
+    let chunk_len = (s.len() / n).max(1);
+
+    let mut chunks = Vec::with_capacity(n);
+
+    let mut rest = s;
+
+    while chunks.len() + 1 < n && rest.len() > chunk_len {
+      match self.next_boundary(rest, chunk_len) {
+        None => break,
+        Some(end) => {
+          let (chunk, remaining) = rest.split_at(end);
+          chunks.push(chunk);
+          rest = remaining;
+        }
+      }
+    }
+
+    chunks.push(rest);
+
+    chunks
+  }
+
+  /// Byte offset of the first character that separates words at or after `from`.
+  fn next_boundary(&self, s: &str, mut from: usize) -> Option<usize> {
+    while !s.is_char_boundary(from) {
+      from += 1;
+    }
+
+    s[from..]
+      .char_indices()
+      .find(|(_, character)| self.separates_words(*character))
+      .map(|(offset, _)| from + offset)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Segmentation;
+  use proptest::prelude::*;
+
+  fn threads(n: usize) -> NonZeroUsize {
+    NonZeroUsize::new(n).unwrap()
+  }
+
+  #[test]
+  fn counts_on_several_threads() {
+    let counter = WordCounter::default();
+
+    let s = "cats dogs birds hello world car ".repeat(100);
+
+    assert_eq!(400, counter.count_parallel(&s, threads(4)));
+    assert_eq!(0, counter.count_parallel("", threads(4)));
+    assert_eq!(1, counter.count_parallel("s", threads(4)));
+  }
+
+  #[test]
+  fn chunks_end_before_a_boundary() {
+    let counter = WordCounter::default();
+    let every_character_is_a_boundary = WordCounter::builder().boundary(|_| true).build();
+
+    assert_eq!(
+      vec!["ab", " cd", " ef"],
+      counter.split_at_boundaries("ab cd ef", 3)
+    );
+    assert_eq!(
+      vec!["catsdogsbirds"],
+      counter.split_at_boundaries("catsdogsbirds", 3)
+    );
+    assert_eq!(
+      vec!["é", "é", "é"],
+      every_character_is_a_boundary.split_at_boundaries("ééé", 3)
+    );
+    // The middle of the input is in the middle of the second "é".
+    assert_eq!(
+      vec!["éé", "é"],
+      every_character_is_a_boundary.split_at_boundaries("ééé", 2)
+    );
+  }
+
+  proptest! {
+    #[test]
+    fn same_result_as_the_sequential_counter(
+      s in "([a-zA-Zé日本 ,.!'\n]|rs|s |r )*",
+      n in 1..16_usize
+    ) {
+      let counters = [
+        WordCounter::default(),
+        WordCounter::builder().suffix("é").prefix("日").build(),
+        WordCounter::builder().suffix("S").case_insensitive(true).build(),
+        WordCounter::builder().boundary(|character| character == ' ').build(),
+        WordCounter::builder().suffix("t").segmentation(Segmentation::Unicode).build(),
+      ];
+
+      for counter in counters {
+        prop_assert_eq!(counter.count(&s), counter.count_parallel(&s, threads(n)));
+      }
+    }
+
+    #[test]
+    fn same_result_as_the_sequential_counter_for_any_text(s in any::<String>(), n in 1..16_usize) {
+      let counters = [
+        WordCounter::default(),
+        WordCounter::builder().segmentation(Segmentation::Unicode).build(),
+      ];
+
+      for counter in counters {
+        prop_assert_eq!(counter.count(&s), counter.count_parallel(&s, threads(n)));
+      }
+    }
+  }
+}
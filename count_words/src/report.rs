@@ -0,0 +1,263 @@
+//! Which words matched, where they are and how often each of them appears.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::WordCounter;
+
+/// A word that matched the rules of a [`WordCounter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WordMatch {
+  pub word: String,
+  /// Byte offsets of the word in the text.
+  pub span: Range<usize>,
+  /// 1-based line of the first character of the word.
+  pub line: usize,
+  /// 1-based column of the first character of the word, counted in characters.
+  pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WordFrequency {
+  pub word: String,
+  pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WordReport {
+  /// Number of words that matched.
+  pub total: usize,
+  /// How many times each matched word appears. Words are compared after
+  /// [`WordCounter::normalize`], so with case-insensitive rules `Bus` and
+  /// `bus` are the same word.
+  pub frequencies: BTreeMap<String, usize>,
+  /// The most frequent words, most frequent first. Words with the same count
+  /// are in alphabetical order.
+  pub top: Vec<WordFrequency>,
+}
+
+impl WordCounter {
+  /// Returns every word in `s` that matches the rules, in the order they appear.
+  pub fn find_matches(&self, s: &str) -> Vec<WordMatch> {
+    let mut matches = vec![];
+
+    let (mut line, mut column) = (1, 1);
+
+    // Byte offset up to which `line` and `column` were computed.
+    let mut position = 0;
+
+    for (offset, word) in self.word_indices(s) {
+      for character in s[position..offset].chars() {
+        if character == '\n' {
+          line += 1;
+          column = 1;
+        } else {
+          column += 1;
+        }
+      }
+
+      position = offset;
+
+      if self.matches(word) {
+        matches.push(WordMatch {
+          word: word.to_string(),
+          span: offset..offset + word.len(),
+          line,
+          column,
+        });
+      }
+    }
+
+    matches
+  }
+
+  /// Returns how many words matched, how often each of them appears and the
+  /// `top` most frequent ones.
+  pub fn report(&self, s: &str, top: usize) -> WordReport {
+    WordReport::from_matches(self, &self.find_matches(s), top)
+  }
+}
+
+impl WordReport {
+  pub fn from_matches(counter: &WordCounter, matches: &[WordMatch], top: usize) -> Self {
+    let mut frequencies = BTreeMap::new();
+
+    for word_match in matches {
+      *frequencies
+        .entry(counter.normalize(&word_match.word).into_owned())
+        .or_insert(0) += 1;
+    }
+
+    let mut most_frequent: Vec<WordFrequency> = frequencies
+      .iter()
+      .map(|(word, count)| WordFrequency {
+        word: word.clone(),
+        count: *count,
+      })
+      .collect();
+
+    // `frequencies` is sorted by word, and the sort is stable, so words with
+    // the same count stay in alphabetical order.
+    most_frequent.sort_by_key(|frequency| Reverse(frequency.count));
+    most_frequent.truncate(top);
+
+    Self {
+      total: matches.len(),
+      frequencies,
+      top: most_frequent,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Segmentation;
+
+  fn word_match(word: &str, span: Range<usize>, line: usize, column: usize) -> WordMatch {
+    WordMatch {
+      word: word.to_string(),
+      span,
+      line,
+      column,
+    }
+  }
+
+  #[test]
+  fn finds_spans_lines_and_columns() {
+    let counter = WordCounter::default();
+
+    assert_eq!(
+      vec![
+        word_match("cats", 0..4, 1, 1),
+        word_match("dogs", 9..13, 1, 10),
+        word_match("bar", 14..17, 2, 1),
+        word_match("cars", 20..24, 4, 2),
+      ],
+      counter.find_matches("cats and dogs\nbar\n\n cars")
+    );
+  }
+
+  #[test]
+  fn columns_count_characters() {
+    let counter = WordCounter::default();
+
+    let matches = counter.find_matches("café cats");
+
+    assert_eq!(vec![word_match("cats", 6..10, 1, 6)], matches);
+  }
+
+  #[test]
+  fn no_matches() {
+    let counter = WordCounter::default();
+
+    assert_eq!(Vec::<WordMatch>::new(), counter.find_matches(""));
+    assert_eq!(Vec::<WordMatch>::new(), counter.find_matches("hello world"));
+  }
+
+  #[test]
+  fn finds_as_many_matches_as_count() {
+    let counter = WordCounter::default();
+
+    let s = "saasaa xxxs aaaaa zzzzs, xr\nr s";
+
+    assert_eq!(counter.count(s), counter.find_matches(s).len());
+  }
+
+  #[test]
+  fn finds_matches_with_unicode_segmentation() {
+    let counter = WordCounter::builder()
+      .suffix("t")
+      .segmentation(Segmentation::Unicode)
+      .build();
+
+    assert_eq!(
+      vec![
+        word_match("don't", 0..5, 1, 1),
+        word_match("shout", 7..12, 2, 2)
+      ],
+      counter.find_matches("don't\n shout")
+    );
+  }
+
+  #[test]
+  fn report_with_frequencies_and_top_words() {
+    let counter = WordCounter::builder().suffix("s").build();
+
+    let report = counter.report("cats dogs cats birds dogs cats ants", 2);
+
+    assert_eq!(7, report.total);
+    assert_eq!(
+      BTreeMap::from([
+        ("ants".to_string(), 1),
+        ("birds".to_string(), 1),
+        ("cats".to_string(), 3),
+        ("dogs".to_string(), 2),
+      ]),
+      report.frequencies
+    );
+    assert_eq!(
+      vec![
+        WordFrequency {
+          word: "cats".to_string(),
+          count: 3
+        },
+        WordFrequency {
+          word: "dogs".to_string(),
+          count: 2
+        },
+      ],
+      report.top
+    );
+  }
+
+  #[test]
+  fn ties_in_the_top_words_are_alphabetical() {
+    let counter = WordCounter::builder().build();
+
+    let report = counter.report("b a c", 2);
+
+    assert_eq!(
+      vec!["a", "b"],
+      report
+        .top
+        .iter()
+        .map(|frequency| frequency.word.as_str())
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn case_insensitive_reports_merge_words() {
+    let counter = WordCounter::builder()
+      .suffix("s")
+      .case_insensitive(true)
+      .build();
+
+    let report = counter.report("BUS bus Bus", 10);
+
+    assert_eq!(BTreeMap::from([("bus".to_string(), 3)]), report.frequencies);
+  }
+
+  #[test]
+  fn serializes_to_json() {
+    let counter = WordCounter::default();
+
+    assert_eq!(
+      serde_json::json!({
+        "total": 3,
+        "frequencies": { "cats": 2, "car": 1 },
+        "top": [{ "word": "cats", "count": 2 }],
+      }),
+      serde_json::to_value(counter.report("cats car cats", 1)).unwrap()
+    );
+
+    assert_eq!(
+      serde_json::json!([{ "word": "cats", "span": { "start": 0, "end": 4 }, "line": 1, "column": 1 }]),
+      serde_json::to_value(counter.find_matches("cats")).unwrap()
+    );
+  }
+}
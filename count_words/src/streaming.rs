@@ -0,0 +1,290 @@
+//! Counts words from a reader without loading the whole input in memory.
+//!
+//! The input is read in chunks. A chunk may end in the middle of a word or
+//! even in the middle of a UTF-8 sequence, so the text after the last place
+//! where words are known to be separated and the bytes of the incomplete
+//! character are carried over to the next chunk. With
+//! [`Segmentation::Unicode`](crate::Segmentation::Unicode) words are known to
+//! be separated at whitespace.
+//!
+//! Only the text decoded since the last cut is searched for the next one, so
+//! a long line or word is not scanned again for every chunk.
+
+use std::io::{self, ErrorKind, Read};
+
+use crate::WordCounter;
+
+const CHUNK_SIZE: usize = 8 * 1024;
+
+impl WordCounter {
+  /// Same as [`WordCounter::count`] but reads the text from `reader`.
+  ///
+  /// Returns an error with [`ErrorKind::InvalidData`] if the input is not
+  /// valid UTF-8.
+  pub fn count_reader<R: Read>(&self, mut reader: R) -> io::Result<usize> {
+    let mut chunk = [0; CHUNK_SIZE];
+
+    // Bytes that were read but not decoded yet because they are the
+    // beginning of a character that continues in the next chunk.
+    let mut pending: Vec<u8> = Vec::with_capacity(CHUNK_SIZE + 4);
+
+    let mut tail = Tail::default();
+
+    let mut count = 0;
+
+    loop {
+      let n = match reader.read(&mut chunk) {
+        Ok(0) => break,
+        Ok(n) => n,
+        Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+        Err(error) => return Err(error),
+      };
+
+      pending.extend_from_slice(&chunk[..n]);
+
+      let valid_up_to = match std::str::from_utf8(&pending) {
+        Ok(text) => text.len(),
+        // `error_len` is `None` when the input ends in the middle of a
+        // character, which is expected at the end of a chunk.
+        Err(error) if error.error_len().is_none() => error.valid_up_to(),
+        Err(error) => return Err(io::Error::new(ErrorKind::InvalidData, error)),
+      };
+
+      count += tail.push(
+        self,
+        std::str::from_utf8(&pending[..valid_up_to]).expect("validated above"),
+      );
+      pending.drain(..valid_up_to);
+    }
+
+    if !pending.is_empty() {
+      return Err(io::Error::new(
+        ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8: it ends in the middle of a character",
+      ));
+    }
+
+    count += self.count(&tail.text);
+
+    Ok(count)
+  }
+}
+
+/// Decoded text that may end in the middle of a word.
+#[derive(Debug, Default)]
+struct Tail {
+  text: String,
+  // How much of `text` was already searched for a place to cut it.
+  scanned: usize,
+}
+
+impl Tail {
+  /// Appends `s`, then counts and drops the text up to the last place where
+  /// words are known to be complete.
+  fn push(&mut self, counter: &WordCounter, s: &str) -> usize {
+    self.text.push_str(s);
+
+    let end = self.text[self.scanned..]
+      .rfind(|character| counter.separates_words(character))
+      .map(|end| self.scanned + end);
+
+    let count = match end {
+      Some(end) => {
+        let count = counter.count(&self.text[..end]);
+        self.text.drain(..end);
+        count
+      }
+      None => 0,
+    };
+
+    self.scanned = self.text.len();
+
+    count
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Segmentation;
+  use proptest::prelude::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use std::sync::Arc;
+
+  /// Returns the data in chunks of the given sizes, cycling through them.
+  struct ChunkedReader {
+    data: Vec<u8>,
+    position: usize,
+    chunk_sizes: Vec<usize>,
+    reads: usize,
+  }
+
+  impl ChunkedReader {
+    fn new(data: &[u8], chunk_sizes: Vec<usize>) -> Self {
+      Self {
+        data: data.to_vec(),
+        position: 0,
+        chunk_sizes,
+        reads: 0,
+      }
+    }
+  }
+
+  impl Read for ChunkedReader {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+      let chunk_size = self.chunk_sizes[self.reads % self.chunk_sizes.len()];
+      self.reads += 1;
+
+      let n = chunk_size
+        .min(buffer.len())
+        .min(self.data.len() - self.position);
+
+      buffer[..n].copy_from_slice(&self.data[self.position..self.position + n]);
+      self.position += n;
+
+      Ok(n)
+    }
+  }
+
+  #[test]
+  fn words_split_across_chunks() {
+    let counter = WordCounter::default();
+
+    let reader = ChunkedReader::new(b"cats dogs birds", vec![1, 2, 3]);
+
+    assert_eq!(3, counter.count_reader(reader).unwrap());
+  }
+
+  #[test]
+  fn characters_split_across_chunks() {
+    let counter = WordCounter::builder().suffix("é").build();
+
+    // "é" takes two bytes, so reading one byte at a time splits every "é".
+    let reader = ChunkedReader::new("ée éé".as_bytes(), vec![1]);
+
+    assert_eq!(1, counter.count_reader(reader).unwrap());
+  }
+
+  #[test]
+  fn invalid_utf8() {
+    let counter = WordCounter::default();
+
+    let error = counter.count_reader(&b"cats \xff dogs"[..]).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, error.kind());
+
+    // The input ends in the middle of "é".
+    let error = counter.count_reader(&"cats é".as_bytes()[..6]).unwrap_err();
+    assert_eq!(ErrorKind::InvalidData, error.kind());
+  }
+
+  #[test]
+  fn retries_interrupted_reads() {
+    struct InterruptedOnce(Option<&'static [u8]>, bool);
+
+    impl Read for InterruptedOnce {
+      fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if !self.1 {
+          self.1 = true;
+          return Err(io::Error::from(ErrorKind::Interrupted));
+        }
+
+        match self.0.take() {
+          None => Ok(0),
+          Some(data) => {
+            buffer[..data.len()].copy_from_slice(data);
+            Ok(data.len())
+          }
+        }
+      }
+    }
+
+    let counter = WordCounter::default();
+
+    assert_eq!(
+      2,
+      counter
+        .count_reader(InterruptedOnce(Some(b"cats dogs"), false))
+        .unwrap()
+    );
+  }
+
+  #[test]
+  fn a_long_word_is_scanned_once() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = WordCounter::builder()
+      .boundary({
+        let calls = Arc::clone(&calls);
+        move |character| {
+          calls.fetch_add(1, Ordering::Relaxed);
+          !character.is_alphabetic()
+        }
+      })
+      .build();
+
+    let word = "a".repeat(1024 * 1024);
+
+    assert_eq!(1, counter.count_reader(word.as_bytes()).unwrap());
+
+    // Once while reading and once while counting.
+    assert!(calls.load(Ordering::Relaxed) <= 2 * word.len());
+  }
+
+  #[test]
+  fn a_long_line_is_not_kept_in_memory() {
+    let counter = WordCounter::builder()
+      .segmentation(Segmentation::Unicode)
+      .build();
+    let line = "don't stop. ".repeat(100_000);
+
+    let mut tail = Tail::default();
+    let mut count = 0;
+
+    for chunk in line.as_bytes().chunks(CHUNK_SIZE) {
+      count += tail.push(&counter, std::str::from_utf8(chunk).unwrap());
+
+      assert!(tail.text.len() < 16, "{:?}", tail.text);
+    }
+
+    count += counter.count(&tail.text);
+
+    assert_eq!(200_000, count);
+  }
+
+  proptest! {
+    #[test]
+    fn same_result_as_the_in_memory_counter(
+      s in "([a-zA-Zé日本 ,.!'\n]|rs|s |r )*",
+      chunk_sizes in proptest::collection::vec(1..16_usize, 1..8)
+    ) {
+      let counters = [
+        WordCounter::default(),
+        WordCounter::builder().suffix("é").prefix("日").build(),
+        WordCounter::builder().suffix("S").case_insensitive(true).build(),
+        WordCounter::builder().suffix("t").segmentation(Segmentation::Unicode).build(),
+      ];
+
+      for counter in counters {
+        let reader = ChunkedReader::new(s.as_bytes(), chunk_sizes.clone());
+
+        prop_assert_eq!(counter.count(&s), counter.count_reader(reader).unwrap());
+      }
+    }
+
+    #[test]
+    fn same_result_as_the_in_memory_counter_for_any_text(
+      s in any::<String>(),
+      chunk_sizes in proptest::collection::vec(1..16_usize, 1..8)
+    ) {
+      let counters = [
+        WordCounter::default(),
+        WordCounter::builder().segmentation(Segmentation::Unicode).build(),
+      ];
+
+      for counter in counters {
+        let reader = ChunkedReader::new(s.as_bytes(), chunk_sizes.clone());
+
+        prop_assert_eq!(counter.count(&s), counter.count_reader(reader).unwrap());
+      }
+    }
+  }
+}
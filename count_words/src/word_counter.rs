@@ -0,0 +1,347 @@
+//! Configurable version of `count_words`.
+//!
+//! By default a word is a maximal run of characters that are not boundaries.
+//! With [`Segmentation::Unicode`] words are found with the Unicode word
+//! boundary rules instead. A word is counted when it starts with one of the
+//! prefixes and ends with one of the suffixes. An empty list of prefixes or
+//! suffixes accepts any word.
+//!
+//! Words and affixes are compared in Unicode normalization form C, so a
+//! precomposed "é" and an "e" followed by a combining accent are the same.
+
+use std::borrow::Cow;
+
+use caseless::Caseless;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Predicate that tells whether a character separates two words.
+pub type Boundary = Box<dyn Fn(char) -> bool + Send + Sync>;
+
+/// How the text is split in words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Segmentation {
+  /// A word is a maximal run of characters that are not boundaries.
+  #[default]
+  Boundary,
+  /// Words are found with the word boundary rules of Unicode Standard Annex
+  /// #29, so "don't" is a single word and every CJK ideograph is a word.
+  /// Hyphens still separate words. The boundary predicate is ignored.
+  Unicode,
+}
+
+pub struct WordCounter {
+  prefixes: Vec<String>,
+  suffixes: Vec<String>,
+  case_insensitive: bool,
+  segmentation: Segmentation,
+  is_boundary: Boundary,
+}
+
+impl WordCounter {
+  pub fn builder() -> WordCounterBuilder {
+    WordCounterBuilder::default()
+  }
+
+  /// Returns the number of words in `s` that match the rules.
+  pub fn count(&self, s: &str) -> usize {
+    self.words(s).filter(|word| self.matches(word)).count()
+  }
+
+  /// Tells whether a single word matches the prefixes and suffixes.
+  pub fn matches(&self, word: &str) -> bool {
+    if word.is_empty() {
+      return false;
+    }
+
+    let word = self.normalize(word);
+
+    let has_prefix = self.prefixes.is_empty()
+      || self
+        .prefixes
+        .iter()
+        .any(|prefix| word.starts_with(prefix.as_str()));
+
+    let has_suffix = self.suffixes.is_empty()
+      || self
+        .suffixes
+        .iter()
+        .any(|suffix| word.ends_with(suffix.as_str()));
+
+    has_prefix && has_suffix
+  }
+
+  /// The form of `word` that is compared with the prefixes and suffixes:
+  /// the NFC form of the word or, when matching is case-insensitive, its
+  /// NFKC case fold, so "BUS", "bus" and "ＢＵＳ" are the same word.
+  pub fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str> {
+    normalize(word, self.case_insensitive)
+  }
+
+  pub fn segmentation(&self) -> Segmentation {
+    self.segmentation
+  }
+
+  /// Tells whether `character` separates two words. Only used with
+  /// [`Segmentation::Boundary`].
+  pub fn is_boundary(&self, character: char) -> bool {
+    (self.is_boundary)(character)
+  }
+
+  /// Tells whether the text can be cut right before `character` without
+  /// changing which words it has. With [`Segmentation::Unicode`] that is true
+  /// for whitespace, since no word boundary rule looks past it.
+  pub(crate) fn separates_words(&self, character: char) -> bool {
+    match self.segmentation {
+      Segmentation::Boundary => self.is_boundary(character),
+      Segmentation::Unicode => character.is_whitespace(),
+    }
+  }
+
+  /// Every word of `s` along with its byte offset.
+  pub(crate) fn word_indices<'a>(
+    &'a self,
+    s: &'a str,
+  ) -> Box<dyn Iterator<Item = (usize, &'a str)> + 'a> {
+    match self.segmentation {
+      Segmentation::Boundary => Box::new(
+        s.split(move |character| self.is_boundary(character))
+          .filter(|word| !word.is_empty())
+          .map(move |word| (word.as_ptr() as usize - s.as_ptr() as usize, word)),
+      ),
+      Segmentation::Unicode => Box::new(s.unicode_word_indices()),
+    }
+  }
+
+  fn words<'a>(&'a self, s: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    self.word_indices(s).map(|(_, word)| word)
+  }
+}
+
+fn normalize(s: &str, case_insensitive: bool) -> Cow<'_, str> {
+  if case_insensitive {
+    Cow::Owned(s.nfkc().default_case_fold().nfkc().collect())
+  } else if is_nfc_quick(s.chars()) == IsNormalized::Yes {
+    Cow::Borrowed(s)
+  } else {
+    Cow::Owned(s.nfc().collect())
+  }
+}
+
+impl Default for WordCounter {
+  /// The rules of `count_words`: words end with either an "s" or an "r" and
+  /// a word ends when a non-letter appears.
+  fn default() -> Self {
+    Self::builder().suffix("s").suffix("r").build()
+  }
+}
+
+pub struct WordCounterBuilder {
+  prefixes: Vec<String>,
+  suffixes: Vec<String>,
+  case_insensitive: bool,
+  segmentation: Segmentation,
+  is_boundary: Boundary,
+}
+
+impl Default for WordCounterBuilder {
+  fn default() -> Self {
+    Self {
+      prefixes: vec![],
+      suffixes: vec![],
+      case_insensitive: false,
+      segmentation: Segmentation::default(),
+      is_boundary: Box::new(|character| !character.is_alphabetic()),
+    }
+  }
+}
+
+impl WordCounterBuilder {
+  pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+    self.prefixes.push(prefix.into());
+    self
+  }
+
+  pub fn prefixes<I, S>(mut self, prefixes: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.prefixes.extend(prefixes.into_iter().map(Into::into));
+    self
+  }
+
+  pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+    self.suffixes.push(suffix.into());
+    self
+  }
+
+  pub fn suffixes<I, S>(mut self, suffixes: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    self.suffixes.extend(suffixes.into_iter().map(Into::into));
+    self
+  }
+
+  pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+    self.case_insensitive = case_insensitive;
+    self
+  }
+
+  pub fn segmentation(mut self, segmentation: Segmentation) -> Self {
+    self.segmentation = segmentation;
+    self
+  }
+
+  /// Replaces the default boundary, which is any character that is not a letter.
+  pub fn boundary<F>(mut self, is_boundary: F) -> Self
+  where
+    F: Fn(char) -> bool + Send + Sync + 'static,
+  {
+    self.is_boundary = Box::new(is_boundary);
+    self
+  }
+
+  pub fn build(self) -> WordCounter {
+    let case_insensitive = self.case_insensitive;
+
+    let normalize_all = |affixes: Vec<String>| -> Vec<String> {
+      affixes
+        .iter()
+        .map(|affix| normalize(affix, case_insensitive).into_owned())
+        .collect()
+    };
+
+    WordCounter {
+      prefixes: normalize_all(self.prefixes),
+      suffixes: normalize_all(self.suffixes),
+      case_insensitive,
+      segmentation: self.segmentation,
+      is_boundary: self.is_boundary,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn default_rules_are_the_ones_from_count_words() {
+    let counter = WordCounter::default();
+
+    assert_eq!(0, counter.count("hello world"));
+    assert_eq!(2, counter.count("saasaa xxxs aaaaa zzzzs"));
+    assert_eq!(2, counter.count("saasaa xxxr aaaaa zzzzr"));
+  }
+
+  #[test]
+  fn multi_character_suffixes() {
+    let counter = WordCounter::builder().suffix("ing").suffix("ed").build();
+
+    assert_eq!(3, counter.count("singing and dancing, they jumped"));
+    assert_eq!(0, counter.count("in"));
+    assert_eq!(1, counter.count("ing"));
+  }
+
+  #[test]
+  fn prefixes() {
+    let counter = WordCounter::builder().prefixes(["un", "re"]).build();
+
+    assert_eq!(3, counter.count("undo redo do unreal"));
+    assert_eq!(0, counter.count("u r"));
+  }
+
+  #[test]
+  fn prefixes_and_suffixes_must_both_match() {
+    let counter = WordCounter::builder().prefix("un").suffix("able").build();
+
+    assert_eq!(2, counter.count("unable unbeatable undo readable"));
+  }
+
+  #[test]
+  fn without_prefixes_or_suffixes_every_word_matches() {
+    let counter = WordCounter::builder().build();
+
+    assert_eq!(4, counter.count("  one two,three...four "));
+    assert_eq!(0, counter.count(""));
+    assert_eq!(0, counter.count("   "));
+  }
+
+  #[test]
+  fn case_insensitive_matching() {
+    let case_sensitive = WordCounter::builder().suffix("ING").build();
+    let case_insensitive = WordCounter::builder()
+      .suffix("ING")
+      .case_insensitive(true)
+      .build();
+
+    assert_eq!(1, case_sensitive.count("SINGING dancing"));
+    assert_eq!(2, case_insensitive.count("SINGING dancing"));
+    assert!(case_insensitive.matches("RunnIng"));
+  }
+
+  #[test]
+  fn custom_boundary() {
+    let counter = WordCounter::builder()
+      .suffix("s")
+      .boundary(|character| character.is_whitespace())
+      .build();
+
+    assert_eq!(2, counter.count("cats, dogs's  birds! rats"));
+    assert!(counter.is_boundary('\n'));
+    assert!(!counter.is_boundary(','));
+  }
+
+  #[test]
+  fn unicode_segmentation() {
+    let boundary = WordCounter::builder().build();
+    let unicode = WordCounter::builder()
+      .segmentation(Segmentation::Unicode)
+      .build();
+
+    assert_eq!(5, boundary.count("don't shout, can't"));
+    assert_eq!(3, unicode.count("don't shout, can't"));
+
+    // Every ideograph is a word.
+    assert_eq!(1, boundary.count("日本語"));
+    assert_eq!(3, unicode.count("日本語"));
+
+    // Numbers are words too, and hyphens separate words.
+    assert_eq!(5, unicode.count("e-mail me at 3.14"));
+  }
+
+  #[test]
+  fn case_folding() {
+    let counter = WordCounter::builder()
+      .suffix("bus")
+      .case_insensitive(true)
+      .build();
+
+    assert_eq!(4, counter.count("BUS bus Bus ＢＵＳ"));
+
+    let counter = WordCounter::builder()
+      .suffix("SSE")
+      .case_insensitive(true)
+      .build();
+
+    // "ß" folds to "ss".
+    assert_eq!(2, counter.count("STRASSE straße"));
+  }
+
+  #[test]
+  fn normalized_comparison() {
+    // A combining accent is not a letter, so it is a boundary unless words are
+    // found with the Unicode rules.
+    let counter = WordCounter::builder()
+      .suffix("\u{e9}")
+      .segmentation(Segmentation::Unicode)
+      .build();
+
+    // "e" followed by a combining acute accent.
+    assert_eq!(1, counter.count("cafe\u{301}"));
+    assert_eq!("caf\u{e9}", counter.normalize("cafe\u{301}"));
+  }
+}
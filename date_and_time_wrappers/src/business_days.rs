@@ -0,0 +1,333 @@
+//! Business days and working hours, for things like "delivery in 3 business
+//! days" or "support is open from 9:00 to 17:00".
+//!
+//! Dates are local dates of the time zone of the [`BusinessCalendar`], so an
+//! instant is a business day or not depending on where the business is.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::{Clock, HolidayCalendar, Today};
+
+#[derive(Debug, Clone)]
+pub struct BusinessCalendar {
+  time_zone: Tz,
+  weekend: BTreeSet<u32>,
+  holidays: HolidayCalendar,
+  closures: BTreeSet<NaiveDate>,
+  opens_at: NaiveTime,
+  closes_at: NaiveTime,
+}
+
+impl BusinessCalendar {
+  /// Open from 9:00 to 17:00, Monday to Friday, in `time_zone`, without
+  /// holidays.
+  pub fn new(time_zone: Tz) -> Self {
+    Self {
+      time_zone,
+      weekend: BTreeSet::new(),
+      holidays: HolidayCalendar::new(),
+      closures: BTreeSet::new(),
+      opens_at: NaiveTime::from_hms(9, 0, 0),
+      closes_at: NaiveTime::from_hms(17, 0, 0),
+    }
+    .with_weekend([Weekday::Sat, Weekday::Sun])
+  }
+
+  /// Replaces the days of the weekend.
+  ///
+  /// # Panics
+  ///
+  /// Panics if every day of the week is part of the weekend.
+  pub fn with_weekend(mut self, weekend: impl IntoIterator<Item = Weekday>) -> Self {
+    self.weekend = weekend
+      .into_iter()
+      .map(|day| day.num_days_from_monday())
+      .collect();
+
+    assert!(
+      self.weekend.len() < 7,
+      "there must be at least one business day in a week"
+    );
+
+    self
+  }
+
+  /// Days on which the observed holidays of `holidays` fall are not business
+  /// days.
+  pub fn with_holidays(mut self, holidays: HolidayCalendar) -> Self {
+    self.holidays = holidays;
+    self
+  }
+
+  /// A single day that is not a business day, such as a company event.
+  pub fn with_closure(mut self, date: NaiveDate) -> Self {
+    self.closures.insert(date);
+    self
+  }
+
+  /// # Panics
+  ///
+  /// Panics if `opens_at` is not before `closes_at`.
+  pub fn with_working_hours(mut self, opens_at: NaiveTime, closes_at: NaiveTime) -> Self {
+    assert!(
+      opens_at < closes_at,
+      "working hours must open before they close. got {} to {}",
+      opens_at,
+      closes_at
+    );
+
+    self.opens_at = opens_at;
+    self.closes_at = closes_at;
+    self
+  }
+
+  pub fn time_zone(&self) -> Tz {
+    self.time_zone
+  }
+
+  pub fn is_business_day(&self, date: NaiveDate) -> bool {
+    !self
+      .weekend
+      .contains(&date.weekday().num_days_from_monday())
+      && !self.closures.contains(&date)
+      && !self.holidays.is_holiday(date)
+  }
+
+  /// The `days`th business day after `date`, or before it if `days` is
+  /// negative. `date` itself is never counted, so adding 0 days returns it.
+  pub fn add_business_days(&self, date: NaiveDate, days: i64) -> NaiveDate {
+    let step = if days < 0 { -1 } else { 1 };
+
+    let mut date = date;
+    let mut remaining = days.abs();
+
+    while remaining > 0 {
+      date += Duration::days(step);
+
+      if self.is_business_day(date) {
+        remaining -= 1;
+      }
+    }
+
+    date
+  }
+
+  /// Number of business days after `start` up to and including `end`, or
+  /// minus the number of business days before `start` down to and including
+  /// `end` when `end` is before `start`.
+  pub fn business_days_between(&self, start: NaiveDate, end: NaiveDate) -> i64 {
+    let (from, to, sign) = if start <= end {
+      (start, end, 1)
+    } else {
+      (end, start, -1)
+    };
+
+    let mut count = 0;
+    let mut date = from.succ();
+
+    while date <= to {
+      if self.is_business_day(date) {
+        count += 1;
+      }
+
+      date = date.succ();
+    }
+
+    // Going backwards counts `end` instead of `start`.
+    if sign < 0 {
+      count += self.is_business_day(from) as i64 - self.is_business_day(to) as i64;
+    }
+
+    sign * count
+  }
+
+  /// Tells whether `instant` is a business day within working hours, in the
+  /// time zone of the calendar. Opening time is included, closing time isn't.
+  pub fn is_working_time(&self, instant: DateTime<Utc>) -> bool {
+    self.is_open(Today::from(instant.with_timezone(&self.time_zone)))
+  }
+
+  /// The local date of the calendar's time zone according to `clock`.
+  pub fn today(&self, clock: &impl Clock) -> NaiveDate {
+    clock.today_in(self.time_zone).date()
+  }
+
+  /// Tells whether it is working time according to `clock`.
+  pub fn is_open_now(&self, clock: &impl Clock) -> bool {
+    self.is_open(clock.today_in(self.time_zone))
+  }
+
+  /// The `days`th business day after today according to `clock`, such as
+  /// the day of a delivery in 3 business days.
+  pub fn business_days_from_today(&self, clock: &impl Clock, days: i64) -> NaiveDate {
+    self.add_business_days(self.today(clock), days)
+  }
+
+  fn is_open(&self, today: Today) -> bool {
+    self.is_business_day(today.date()) && self.opens_at <= today.time && today.time < self.closes_at
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::time_zone;
+  use crate::{FixedClock, Holiday, HolidayRule, ManualClock, Observance};
+  use chrono::TimeZone;
+  use proptest::prelude::*;
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd(year, month, day)
+  }
+
+  fn new_york() -> BusinessCalendar {
+    BusinessCalendar::new(time_zone("America/New_York").unwrap()).with_holidays(
+      HolidayCalendar::new().with(
+        Holiday::new("Christmas", HolidayRule::Fixed { month: 12, day: 25 })
+          .observed(Observance::NearestWeekday),
+      ),
+    )
+  }
+
+  #[test]
+  fn weekends_and_holidays_are_not_business_days() {
+    let calendar = new_york().with_closure(date(2021, 12, 27));
+
+    // Thursday.
+    assert!(calendar.is_business_day(date(2021, 12, 23)));
+    // Christmas 2021 is a Saturday, observed on Friday.
+    assert!(!calendar.is_business_day(date(2021, 12, 24)));
+    assert!(!calendar.is_business_day(date(2021, 12, 25)));
+    assert!(!calendar.is_business_day(date(2021, 12, 26)));
+    assert!(!calendar.is_business_day(date(2021, 12, 27)));
+    assert!(calendar.is_business_day(date(2021, 12, 28)));
+  }
+
+  #[test]
+  fn adding_business_days() {
+    let calendar = new_york();
+
+    // Wednesday plus 3 business days skips Christmas and the weekend.
+    assert_eq!(
+      date(2021, 12, 28),
+      calendar.add_business_days(date(2021, 12, 22), 3)
+    );
+    assert_eq!(
+      date(2021, 12, 22),
+      calendar.add_business_days(date(2021, 12, 28), -3)
+    );
+    assert_eq!(
+      date(2021, 12, 25),
+      calendar.add_business_days(date(2021, 12, 25), 0)
+    );
+    // From a Saturday the next business day is Monday.
+    assert_eq!(
+      date(2022, 1, 3),
+      calendar.add_business_days(date(2022, 1, 1), 1)
+    );
+  }
+
+  #[test]
+  fn counting_business_days() {
+    let calendar = new_york();
+
+    assert_eq!(
+      3,
+      calendar.business_days_between(date(2021, 12, 22), date(2021, 12, 28))
+    );
+    assert_eq!(
+      -3,
+      calendar.business_days_between(date(2021, 12, 28), date(2021, 12, 22))
+    );
+    assert_eq!(
+      0,
+      calendar.business_days_between(date(2021, 12, 24), date(2021, 12, 26))
+    );
+    assert_eq!(
+      0,
+      calendar.business_days_between(date(2021, 12, 22), date(2021, 12, 22))
+    );
+  }
+
+  #[test]
+  fn configurable_weekend() {
+    // Friday and Saturday.
+    let calendar = BusinessCalendar::new(time_zone("Asia/Dubai").unwrap())
+      .with_weekend([Weekday::Fri, Weekday::Sat]);
+
+    // Thursday plus 1 business day is Sunday.
+    assert_eq!(
+      date(2021, 12, 26),
+      calendar.add_business_days(date(2021, 12, 23), 1)
+    );
+    assert!(calendar.is_business_day(date(2021, 12, 26)));
+  }
+
+  #[test]
+  #[should_panic(expected = "at least one business day")]
+  fn a_week_needs_a_business_day() {
+    BusinessCalendar::new(chrono_tz::UTC).with_weekend([
+      Weekday::Mon,
+      Weekday::Tue,
+      Weekday::Wed,
+      Weekday::Thu,
+      Weekday::Fri,
+      Weekday::Sat,
+      Weekday::Sun,
+    ]);
+  }
+
+  #[test]
+  fn working_hours_in_the_calendar_time_zone() {
+    let calendar =
+      new_york().with_working_hours(NaiveTime::from_hms(8, 30, 0), NaiveTime::from_hms(18, 0, 0));
+
+    // 8:30 in New York is 13:30 UTC in winter.
+    assert!(!calendar.is_working_time(Utc.ymd(2021, 12, 22).and_hms(13, 29, 59)));
+    assert!(calendar.is_working_time(Utc.ymd(2021, 12, 22).and_hms(13, 30, 0)));
+    assert!(calendar.is_working_time(Utc.ymd(2021, 12, 22).and_hms(22, 59, 59)));
+    assert!(!calendar.is_working_time(Utc.ymd(2021, 12, 22).and_hms(23, 0, 0)));
+
+    // ...and 12:30 UTC in summer.
+    assert!(calendar.is_working_time(Utc.ymd(2021, 6, 22).and_hms(12, 30, 0)));
+
+    // Friday afternoon in UTC but the observed Christmas in New York.
+    assert!(!calendar.is_working_time(Utc.ymd(2021, 12, 24).and_hms(15, 0, 0)));
+  }
+
+  #[test]
+  fn business_days_from_the_clock() {
+    let calendar = new_york();
+
+    // Thursday 23 in UTC but still Wednesday 22 in New York.
+    let clock = ManualClock::new(Utc.ymd(2021, 12, 23).and_hms(2, 0, 0));
+
+    assert_eq!(date(2021, 12, 22), calendar.today(&clock));
+    assert_eq!(
+      date(2021, 12, 28),
+      calendar.business_days_from_today(&clock, 3)
+    );
+    assert!(!calendar.is_open_now(&clock));
+
+    clock.advance(Duration::hours(13));
+    assert!(calendar.is_open_now(&clock));
+
+    let saturday = FixedClock::new(Utc.ymd(2021, 12, 18).and_hms(15, 0, 0));
+    assert!(!calendar.is_open_now(&saturday));
+  }
+
+  proptest! {
+    #[test]
+    fn counting_undoes_adding(days in -60..60_i64, offset in 0..3650_i64) {
+      let calendar = new_york().with_closure(date(2021, 7, 2));
+
+      let start = date(2015, 1, 1) + Duration::days(offset);
+      let end = calendar.add_business_days(start, days);
+
+      prop_assert_eq!(days, calendar.business_days_between(start, end));
+    }
+  }
+}
@@ -0,0 +1,253 @@
+//! Clocks that code which depends on the current time receives instead of
+//! calling `chrono::Utc::now()` directly, so tests can decide what time it is.
+
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use thiserror::Error;
+
+#[cfg_attr(test, mockall::automock)]
+pub trait Clock {
+  fn now(&self) -> DateTime<Utc>;
+
+  /// The date and time of a single instant. Reading the month and the day
+  /// from separate calls to [`Clock::now`] could mix two different days.
+  fn today(&self) -> Today {
+    Today::from(self.now())
+  }
+
+  /// Same as [`Clock::today`] but the date and time are the local ones of
+  /// `time_zone`.
+  fn today_in(&self, time_zone: Tz) -> Today {
+    Today::from(self.now().with_timezone(&time_zone))
+  }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("unknown time zone {0:?}, expected an IANA name such as \"America/New_York\"")]
+pub struct UnknownTimeZone(pub String);
+
+/// Looks up a time zone of the bundled tz database by its IANA name.
+pub fn time_zone(name: &str) -> Result<Tz, UnknownTimeZone> {
+  name.parse().map_err(|_| UnknownTimeZone(name.to_string()))
+}
+
+/// The date and time of a single instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Today {
+  pub year: i32,
+  pub month: u32,
+  pub day: u32,
+  pub weekday: Weekday,
+  pub time: NaiveTime,
+}
+
+impl Today {
+  pub fn date(&self) -> NaiveDate {
+    NaiveDate::from_ymd(self.year, self.month, self.day)
+  }
+}
+
+impl<Tz: chrono::TimeZone> From<DateTime<Tz>> for Today {
+  fn from(now: DateTime<Tz>) -> Self {
+    Self {
+      year: now.year(),
+      month: now.month(),
+      day: now.day(),
+      weekday: now.weekday(),
+      time: NaiveTime::from_hms_nano(now.hour(), now.minute(), now.second(), now.nanosecond()),
+    }
+  }
+}
+
+/// The time of the system.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+  fn now(&self) -> DateTime<Utc> {
+    Utc::now()
+  }
+}
+
+/// A clock that is stopped at a given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock {
+  now: DateTime<Utc>,
+}
+
+impl FixedClock {
+  pub fn new(now: DateTime<Utc>) -> Self {
+    Self { now }
+  }
+}
+
+impl Clock for FixedClock {
+  fn now(&self) -> DateTime<Utc> {
+    self.now
+  }
+}
+
+/// A clock that only moves when it is told to.
+///
+/// Clones share the same time, so a test can keep a clone and move the time
+/// of the clock it handed to the code under test.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+  now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+  pub fn new(now: DateTime<Utc>) -> Self {
+    Self {
+      now: Arc::new(Mutex::new(now)),
+    }
+  }
+
+  /// Moves the time forward, or backwards if `duration` is negative.
+  pub fn advance(&self, duration: Duration) {
+    let mut now = self.now.lock().unwrap();
+    *now = *now + duration;
+  }
+
+  pub fn set(&self, now: DateTime<Utc>) {
+    *self.now.lock().unwrap() = now;
+  }
+}
+
+impl Clock for ManualClock {
+  fn now(&self) -> DateTime<Utc> {
+    *self.now.lock().unwrap()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+
+  #[test]
+  fn fixed_clock_does_not_move() {
+    let now = Utc.ymd(2021, 12, 25).and_hms(10, 0, 0);
+
+    let clock = FixedClock::new(now);
+
+    assert_eq!(now, clock.now());
+    assert_eq!(now, clock.now());
+    assert_eq!(
+      Today {
+        year: 2021,
+        month: 12,
+        day: 25,
+        weekday: Weekday::Sat,
+        time: NaiveTime::from_hms(10, 0, 0),
+      },
+      clock.today()
+    );
+    assert_eq!(NaiveDate::from_ymd(2021, 12, 25), clock.today().date());
+  }
+
+  #[test]
+  fn today_in_a_time_zone() {
+    let clock = FixedClock::new(Utc.ymd(2021, 12, 24).and_hms(20, 30, 0));
+
+    let today = clock.today_in(time_zone("Asia/Tokyo").unwrap());
+
+    assert_eq!(NaiveDate::from_ymd(2021, 12, 25), today.date());
+    assert_eq!(Weekday::Sat, today.weekday);
+    assert_eq!(NaiveTime::from_hms(5, 30, 0), today.time);
+  }
+
+  #[test]
+  fn daylight_saving_time() {
+    let new_york = time_zone("America/New_York").unwrap();
+
+    // Clocks jump from 2:00 to 3:00 on March 14 2021, at 7:00 UTC.
+    let clock = ManualClock::new(Utc.ymd(2021, 3, 14).and_hms(6, 59, 0));
+    assert_eq!(NaiveTime::from_hms(1, 59, 0), clock.today_in(new_york).time);
+
+    clock.advance(Duration::minutes(1));
+    assert_eq!(NaiveTime::from_hms(3, 0, 0), clock.today_in(new_york).time);
+
+    // Clocks go back from 2:00 to 1:00 on November 7 2021, at 6:00 UTC, so
+    // 1:30 happens twice.
+    clock.set(Utc.ymd(2021, 11, 7).and_hms(5, 30, 0));
+    assert_eq!(NaiveTime::from_hms(1, 30, 0), clock.today_in(new_york).time);
+
+    clock.advance(Duration::hours(1));
+    assert_eq!(NaiveTime::from_hms(1, 30, 0), clock.today_in(new_york).time);
+    assert_eq!(
+      NaiveDate::from_ymd(2021, 11, 7),
+      clock.today_in(new_york).date()
+    );
+  }
+
+  #[test]
+  fn date_line() {
+    let clock = FixedClock::new(Utc.ymd(2021, 12, 24).and_hms(10, 30, 0));
+
+    // UTC+14 and UTC-11 are on different days for most of the day.
+    assert_eq!(
+      NaiveDate::from_ymd(2021, 12, 25),
+      clock
+        .today_in(time_zone("Pacific/Kiritimati").unwrap())
+        .date()
+    );
+    assert_eq!(
+      NaiveDate::from_ymd(2021, 12, 23),
+      clock
+        .today_in(time_zone("Pacific/Pago_Pago").unwrap())
+        .date()
+    );
+
+    // Samoa moved to the other side of the date line by skipping December 30 2011.
+    let apia = time_zone("Pacific/Apia").unwrap();
+
+    let clock = ManualClock::new(Utc.ymd(2011, 12, 30).and_hms(9, 59, 59));
+    assert_eq!(
+      NaiveDate::from_ymd(2011, 12, 29),
+      clock.today_in(apia).date()
+    );
+
+    clock.advance(Duration::seconds(1));
+    assert_eq!(
+      NaiveDate::from_ymd(2011, 12, 31),
+      clock.today_in(apia).date()
+    );
+  }
+
+  #[test]
+  fn unknown_time_zones() {
+    assert_eq!(
+      Err(UnknownTimeZone("Mars/Olympus_Mons".to_string())),
+      time_zone("Mars/Olympus_Mons")
+    );
+  }
+
+  #[test]
+  fn manual_clock_moves_when_told_to() {
+    let clock = ManualClock::new(Utc.ymd(2021, 12, 24).and_hms(23, 0, 0));
+
+    // Clones share the time.
+    let handle = clock.clone();
+
+    handle.advance(Duration::hours(2));
+    assert_eq!(Utc.ymd(2021, 12, 25).and_hms(1, 0, 0), clock.now());
+
+    handle.advance(Duration::minutes(-30));
+    assert_eq!(Utc.ymd(2021, 12, 25).and_hms(0, 30, 0), clock.now());
+
+    handle.set(Utc.ymd(2022, 1, 1).and_hms(0, 0, 0));
+    assert_eq!(Utc.ymd(2022, 1, 1).and_hms(0, 0, 0), clock.now());
+  }
+
+  #[test]
+  fn system_clock_is_close_to_the_system_time() {
+    let before = Utc::now();
+    let now = SystemClock.now();
+    let after = Utc::now();
+
+    assert!(before <= now && now <= after);
+  }
+}
@@ -0,0 +1,578 @@
+//! Holidays defined by rules, such as "the fourth Thursday of November" or
+//! "two days before Easter", and discounts that apply on them.
+
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use thiserror::Error;
+
+use crate::clock::{self, UnknownTimeZone};
+use crate::{BasisPoints, Clock, Money, RoundingMode, Today};
+
+const MILLISECONDS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+/// How the date of a holiday is computed for a given year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolidayRule {
+  /// The same day every year, such as December 25.
+  Fixed { month: u32, day: u32 },
+  /// The `n`th (1-based) `weekday` of a month, such as the fourth Thursday of
+  /// November.
+  NthWeekday {
+    n: u32,
+    weekday: Weekday,
+    month: u32,
+  },
+  /// The last `weekday` of a month, such as the last Monday of May.
+  LastWeekday { weekday: Weekday, month: u32 },
+  /// A number of days after Easter Sunday, or before if it is negative.
+  Easter { offset_days: i64 },
+}
+
+impl HolidayRule {
+  /// Returns `None` when the rule has no date in `year`, such as February 29
+  /// in a year that is not a leap year or the fifth Monday of a month that
+  /// only has four.
+  pub fn date(&self, year: i32) -> Option<NaiveDate> {
+    match *self {
+      HolidayRule::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+      HolidayRule::NthWeekday { n, weekday, month } => {
+        if n == 0 {
+          return None;
+        }
+
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+
+        let offset =
+          (7 + weekday.num_days_from_monday() - first.weekday().num_days_from_monday()) % 7;
+
+        let day = (n - 1).checked_mul(7)?.checked_add(1 + offset)?;
+
+        NaiveDate::from_ymd_opt(year, month, day)
+      }
+      HolidayRule::LastWeekday { weekday, month } => {
+        let first_of_next_month = if month == 12 {
+          NaiveDate::from_ymd_opt(year.checked_add(1)?, 1, 1)
+        } else {
+          NaiveDate::from_ymd_opt(year, month.checked_add(1)?, 1)
+        }?;
+
+        let last = first_of_next_month.pred_opt()?;
+
+        let offset =
+          (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+
+        last.checked_sub_signed(Duration::days(offset as i64))
+      }
+      HolidayRule::Easter { offset_days } => {
+        // `Duration::days` panics when the offset does not fit in a
+        // duration, `Duration::milliseconds` never does.
+        let offset = Duration::milliseconds(offset_days.checked_mul(MILLISECONDS_PER_DAY)?);
+
+        easter_sunday(year)?.checked_add_signed(offset)
+      }
+    }
+  }
+}
+
+/// Which day a holiday is observed on when it falls on a weekend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Observance {
+  /// The holiday is observed on its date.
+  #[default]
+  Actual,
+  /// Saturday holidays are observed on Friday and Sunday holidays on Monday.
+  NearestWeekday,
+  /// Weekend holidays are observed on the following Monday.
+  FollowingMonday,
+}
+
+impl Observance {
+  /// Returns `None` when the observed day is out of the range of
+  /// [`NaiveDate`].
+  pub fn observed(&self, date: NaiveDate) -> Option<NaiveDate> {
+    match (self, date.weekday()) {
+      (Observance::NearestWeekday, Weekday::Sat) => date.pred_opt(),
+      (Observance::NearestWeekday, Weekday::Sun) => date.succ_opt(),
+      (Observance::FollowingMonday, Weekday::Sat) => date.checked_add_signed(Duration::days(2)),
+      (Observance::FollowingMonday, Weekday::Sun) => date.succ_opt(),
+      _ => Some(date),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+  name: String,
+  rule: HolidayRule,
+  observance: Observance,
+}
+
+impl Holiday {
+  pub fn new(name: impl Into<String>, rule: HolidayRule) -> Self {
+    Self {
+      name: name.into(),
+      rule,
+      observance: Observance::default(),
+    }
+  }
+
+  pub fn observed(mut self, observance: Observance) -> Self {
+    self.observance = observance;
+    self
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The day the holiday is observed on in `year`.
+  pub fn date(&self, year: i32) -> Option<NaiveDate> {
+    self
+      .rule
+      .date(year)
+      .and_then(|date| self.observance.observed(date))
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HolidayCalendar {
+  holidays: Vec<Holiday>,
+}
+
+impl HolidayCalendar {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with(mut self, holiday: Holiday) -> Self {
+    self.holidays.push(holiday);
+    self
+  }
+
+  pub fn holiday(&self, name: &str) -> Option<&Holiday> {
+    self.holidays.iter().find(|holiday| holiday.name == name)
+  }
+
+  /// The day the holiday called `name` is observed on in `year`.
+  pub fn date_of(&self, name: &str, year: i32) -> Option<NaiveDate> {
+    self.holiday(name).and_then(|holiday| holiday.date(year))
+  }
+
+  /// The holidays that are observed on `date`.
+  pub fn holidays_on(&self, date: NaiveDate) -> Vec<&Holiday> {
+    self
+      .holidays
+      .iter()
+      .filter(|holiday| {
+        // Shifting a weekend holiday may move it to the previous or the next
+        // year, such as a Saturday January 1 observed on December 31.
+        (date.year() - 1..=date.year() + 1).any(|year| holiday.date(year) == Some(date))
+      })
+      .collect()
+  }
+
+  pub fn is_holiday(&self, date: NaiveDate) -> bool {
+    !self.holidays_on(date).is_empty()
+  }
+}
+
+/// Easter Sunday of the Gregorian calendar, computed with the anonymous
+/// Gregorian algorithm.
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+  let a = year % 19;
+  let b = year / 100;
+  let c = year % 100;
+  let d = b / 4;
+  let e = b % 4;
+  let f = (b + 8) / 25;
+  let g = (b - f + 1) / 3;
+  let h = (19 * a + b - d - g + 15) % 30;
+  let i = c / 4;
+  let k = c % 4;
+  let l = (32 + 2 * e + 2 * i - h - k) % 7;
+  let m = (a + 11 * h + 22 * l) / 451;
+  let month = (h + l - 7 * m + 114) / 31;
+  let day = (h + l - 7 * m + 114) % 31 + 1;
+
+  NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HolidayDiscountError {
+  #[error("there is no holiday called {0:?} in the calendar")]
+  UnknownHoliday(String),
+}
+
+/// Applies a discount on the holidays it is attached to.
+pub struct HolidayDiscount<C: Clock> {
+  clock: C,
+  calendar: HolidayCalendar,
+  discounts: HashMap<String, BasisPoints>,
+  rounding: RoundingMode,
+}
+
+impl<C: Clock> HolidayDiscount<C> {
+  pub fn new(clock: C, calendar: HolidayCalendar) -> Self {
+    Self {
+      clock,
+      calendar,
+      discounts: HashMap::new(),
+      rounding: RoundingMode::default(),
+    }
+  }
+
+  pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+    self.rounding = rounding;
+    self
+  }
+
+  /// Applies `discount` on the holiday called `holiday`.
+  pub fn attach(
+    &mut self,
+    holiday: &str,
+    discount: BasisPoints,
+  ) -> Result<(), HolidayDiscountError> {
+    if self.calendar.holiday(holiday).is_none() {
+      return Err(HolidayDiscountError::UnknownHoliday(holiday.to_string()));
+    }
+
+    self.discounts.insert(holiday.to_string(), discount);
+
+    Ok(())
+  }
+
+  /// The discount of today. When several holidays are observed today the
+  /// biggest of their discounts is used.
+  pub fn discount(&self) -> BasisPoints {
+    self.discount_on(self.clock.today())
+  }
+
+  pub fn apply_discount(&self, amount: &Money) -> Money {
+    amount.discounted(self.discount(), self.rounding)
+  }
+
+  /// Same as [`HolidayDiscount::apply_discount`] but holidays are observed
+  /// on the local date of `time_zone`, an IANA name such as "Asia/Tokyo".
+  pub fn apply_discount_in(
+    &self,
+    amount: &Money,
+    time_zone: &str,
+  ) -> Result<Money, UnknownTimeZone> {
+    let today = self.clock.today_in(clock::time_zone(time_zone)?);
+
+    Ok(amount.discounted(self.discount_on(today), self.rounding))
+  }
+
+  fn discount_on(&self, today: Today) -> BasisPoints {
+    self
+      .calendar
+      .holidays_on(today.date())
+      .into_iter()
+      .filter_map(|holiday| self.discounts.get(holiday.name()).copied())
+      .max()
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{FixedClock, ManualClock};
+  use chrono::{TimeZone, Utc};
+
+  fn usd(amount: &str) -> Money {
+    format!("{} USD", amount).parse().unwrap()
+  }
+
+  fn bp(basis_points: u32) -> BasisPoints {
+    BasisPoints::new(basis_points).unwrap()
+  }
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd(year, month, day)
+  }
+
+  fn calendar() -> HolidayCalendar {
+    HolidayCalendar::new()
+      .with(
+        Holiday::new("New Year's Day", HolidayRule::Fixed { month: 1, day: 1 })
+          .observed(Observance::NearestWeekday),
+      )
+      .with(Holiday::new(
+        "Good Friday",
+        HolidayRule::Easter { offset_days: -2 },
+      ))
+      .with(Holiday::new(
+        "Memorial Day",
+        HolidayRule::LastWeekday {
+          weekday: Weekday::Mon,
+          month: 5,
+        },
+      ))
+      .with(Holiday::new(
+        "Thanksgiving",
+        HolidayRule::NthWeekday {
+          n: 4,
+          weekday: Weekday::Thu,
+          month: 11,
+        },
+      ))
+      .with(
+        Holiday::new("Christmas", HolidayRule::Fixed { month: 12, day: 25 })
+          .observed(Observance::NearestWeekday),
+      )
+  }
+
+  #[test]
+  fn fixed_dates() {
+    let rule = HolidayRule::Fixed { month: 2, day: 29 };
+
+    assert_eq!(Some(date(2024, 2, 29)), rule.date(2024));
+    assert_eq!(None, rule.date(2023));
+  }
+
+  #[test]
+  fn nth_weekday() {
+    let thanksgiving = HolidayRule::NthWeekday {
+      n: 4,
+      weekday: Weekday::Thu,
+      month: 11,
+    };
+
+    assert_eq!(Some(date(2021, 11, 25)), thanksgiving.date(2021));
+    assert_eq!(Some(date(2022, 11, 24)), thanksgiving.date(2022));
+    // November 1 2018 is a Thursday.
+    assert_eq!(Some(date(2018, 11, 22)), thanksgiving.date(2018));
+
+    let fifth_monday = HolidayRule::NthWeekday {
+      n: 5,
+      weekday: Weekday::Mon,
+      month: 2,
+    };
+
+    assert_eq!(None, fifth_monday.date(2021));
+  }
+
+  #[test]
+  fn last_weekday() {
+    let memorial_day = HolidayRule::LastWeekday {
+      weekday: Weekday::Mon,
+      month: 5,
+    };
+
+    // May 31 2021 is a Monday.
+    assert_eq!(Some(date(2021, 5, 31)), memorial_day.date(2021));
+    assert_eq!(Some(date(2022, 5, 30)), memorial_day.date(2022));
+
+    let last_friday_of_december = HolidayRule::LastWeekday {
+      weekday: Weekday::Fri,
+      month: 12,
+    };
+
+    assert_eq!(Some(date(2021, 12, 31)), last_friday_of_december.date(2021));
+  }
+
+  #[test]
+  fn easter() {
+    assert_eq!(Some(date(1818, 3, 22)), easter_sunday(1818));
+    assert_eq!(Some(date(2000, 4, 23)), easter_sunday(2000));
+    assert_eq!(Some(date(2021, 4, 4)), easter_sunday(2021));
+    assert_eq!(Some(date(2024, 3, 31)), easter_sunday(2024));
+    assert_eq!(Some(date(2038, 4, 25)), easter_sunday(2038));
+
+    assert_eq!(
+      Some(date(2021, 4, 2)),
+      HolidayRule::Easter { offset_days: -2 }.date(2021)
+    );
+  }
+
+  #[test]
+  fn observed_days() {
+    let christmas = date(2021, 12, 25);
+
+    assert_eq!(Some(christmas), Observance::Actual.observed(christmas));
+    assert_eq!(
+      Some(date(2021, 12, 24)),
+      Observance::NearestWeekday.observed(christmas)
+    );
+    assert_eq!(
+      Some(date(2021, 12, 27)),
+      Observance::FollowingMonday.observed(christmas)
+    );
+    assert_eq!(
+      Some(date(2022, 12, 26)),
+      Observance::NearestWeekday.observed(date(2022, 12, 25))
+    );
+  }
+
+  #[test]
+  fn out_of_range_dates_do_not_exist() {
+    assert_eq!(
+      None,
+      HolidayRule::NthWeekday {
+        n: u32::MAX,
+        weekday: Weekday::Mon,
+        month: 1,
+      }
+      .date(2021)
+    );
+    assert_eq!(
+      None,
+      HolidayRule::Easter {
+        offset_days: i64::MAX
+      }
+      .date(2021)
+    );
+    assert_eq!(
+      None,
+      HolidayRule::Easter {
+        offset_days: i64::MIN
+      }
+      .date(2021)
+    );
+    assert_eq!(
+      None,
+      HolidayRule::Easter {
+        offset_days: 1_000_000_000
+      }
+      .date(2021)
+    );
+    assert_eq!(
+      None,
+      HolidayRule::LastWeekday {
+        weekday: Weekday::Fri,
+        month: 12,
+      }
+      .date(i32::MAX)
+    );
+    assert_eq!(
+      None,
+      HolidayRule::LastWeekday {
+        weekday: Weekday::Fri,
+        month: u32::MAX,
+      }
+      .date(2021)
+    );
+  }
+
+  #[test]
+  fn holidays_on_a_date() {
+    let calendar = calendar();
+
+    let names = |date| {
+      calendar
+        .holidays_on(date)
+        .iter()
+        .map(|holiday| holiday.name().to_string())
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(vec!["Thanksgiving"], names(date(2021, 11, 25)));
+    assert_eq!(vec!["Christmas"], names(date(2021, 12, 24)));
+    assert!(names(date(2021, 12, 25)).is_empty());
+    // January 1 2022 is a Saturday.
+    assert_eq!(vec!["New Year's Day"], names(date(2021, 12, 31)));
+    assert!(calendar.is_holiday(date(2021, 4, 2)));
+    assert!(!calendar.is_holiday(date(2021, 4, 3)));
+
+    assert_eq!(
+      Some(date(2021, 12, 31)),
+      calendar.date_of("New Year's Day", 2022)
+    );
+    assert_eq!(None, calendar.date_of("Halloween", 2022));
+  }
+
+  #[test]
+  fn discounts_on_holidays() {
+    let at = |year, month, day| FixedClock::new(Utc.ymd(year, month, day).and_hms(12, 0, 0));
+
+    let discount = |clock| {
+      let mut discount = HolidayDiscount::new(clock, calendar());
+      discount.attach("Thanksgiving", bp(2000)).unwrap();
+      discount.attach("Christmas", bp(1500)).unwrap();
+      discount
+    };
+
+    assert_eq!(
+      usd("80.00"),
+      discount(at(2021, 11, 25)).apply_discount(&usd("100.00"))
+    );
+    assert_eq!(
+      usd("85.00"),
+      discount(at(2021, 12, 24)).apply_discount(&usd("100.00"))
+    );
+    assert_eq!(
+      usd("100.00"),
+      discount(at(2021, 12, 25)).apply_discount(&usd("100.00"))
+    );
+    // Good Friday is in the calendar but has no discount.
+    assert_eq!(
+      usd("100.00"),
+      discount(at(2021, 4, 2)).apply_discount(&usd("100.00"))
+    );
+  }
+
+  #[test]
+  fn biggest_discount_wins() {
+    let calendar = HolidayCalendar::new()
+      .with(Holiday::new("A", HolidayRule::Fixed { month: 3, day: 1 }))
+      .with(Holiday::new("B", HolidayRule::Fixed { month: 3, day: 1 }));
+
+    let mut discount = HolidayDiscount::new(
+      FixedClock::new(Utc.ymd(2021, 3, 1).and_hms(0, 0, 0)),
+      calendar,
+    );
+    discount.attach("A", bp(1000)).unwrap();
+    discount.attach("B", bp(3000)).unwrap();
+
+    assert_eq!(bp(3000), discount.discount());
+  }
+
+  #[test]
+  fn attaching_discounts_to_unknown_holidays() {
+    let mut discount = HolidayDiscount::new(
+      FixedClock::new(Utc.ymd(2021, 3, 1).and_hms(0, 0, 0)),
+      calendar(),
+    );
+
+    assert_eq!(
+      Err(HolidayDiscountError::UnknownHoliday(
+        "Halloween".to_string()
+      )),
+      discount.attach("Halloween", bp(1000))
+    );
+  }
+
+  #[test]
+  fn holidays_in_the_time_zone_of_the_customer() {
+    let calendar = HolidayCalendar::new().with(Holiday::new(
+      "Pi Day",
+      HolidayRule::Fixed { month: 3, day: 14 },
+    ));
+
+    // Daylight saving time starts in New York on March 14 2021, so midnight
+    // on March 15 is 4:00 UTC instead of 5:00 UTC.
+    let clock = ManualClock::new(Utc.ymd(2021, 3, 15).and_hms(3, 59, 0));
+
+    let mut discount = HolidayDiscount::new(clock.clone(), calendar);
+    discount.attach("Pi Day", bp(1000)).unwrap();
+
+    assert_eq!(usd("100.00"), discount.apply_discount(&usd("100.00")));
+    assert_eq!(
+      Ok(usd("90.00")),
+      discount.apply_discount_in(&usd("100.00"), "America/New_York")
+    );
+
+    clock.advance(chrono::Duration::minutes(1));
+    assert_eq!(
+      Ok(usd("100.00")),
+      discount.apply_discount_in(&usd("100.00"), "America/New_York")
+    );
+
+    assert_eq!(
+      Err(UnknownTimeZone("Nowhere".to_string())),
+      discount.apply_discount_in(&usd("100.00"), "Nowhere")
+    );
+  }
+}
@@ -0,0 +1,305 @@
+//! We can create a wrapper type that has operations related to time and date.
+//! The wrapper type should be easily mockable to make testing code
+//! that depends on a specific time or date easier.
+
+pub mod business_days;
+pub mod clock;
+pub mod holidays;
+pub mod money;
+pub mod promotions;
+pub mod scheduler;
+
+pub use business_days::BusinessCalendar;
+pub use clock::{time_zone, Clock, FixedClock, ManualClock, SystemClock, Today, UnknownTimeZone};
+pub use holidays::{Holiday, HolidayCalendar, HolidayDiscount, HolidayRule, Observance};
+pub use money::{BasisPoints, Currency, Money, RoundingMode};
+pub use promotions::{OverlapPolicy, Promotion, Promotions};
+pub use scheduler::{Scheduler, TimerId};
+
+pub struct ChristmasDiscount<C: Clock> {
+  clock: C,
+  discount: BasisPoints,
+  rounding: RoundingMode,
+}
+
+impl<C: Clock> ChristmasDiscount<C> {
+  /// A 15% discount, rounded half to even.
+  pub fn new(clock: C) -> Self {
+    Self {
+      clock,
+      discount: BasisPoints::new(1500).unwrap(),
+      rounding: RoundingMode::default(),
+    }
+  }
+
+  pub fn with_discount(mut self, discount: BasisPoints) -> Self {
+    self.discount = discount;
+    self
+  }
+
+  pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+    self.rounding = rounding;
+    self
+  }
+}
+
+impl<C: Clock> ChristmasDiscount<C> {
+  fn is_christmas(today: Today) -> bool {
+    today.month == 12 && today.day == 25
+  }
+
+  fn discount(&self, amount: &Money, today: Today) -> Money {
+    // Without a discount the amount is still rounded, so that it has the
+    // same scale on every day.
+    let discount = if Self::is_christmas(today) {
+      self.discount
+    } else {
+      BasisPoints::default()
+    };
+
+    amount.discounted(discount, self.rounding)
+  }
+
+  pub fn apply_discount(&self, amount: &Money) -> Money {
+    self.discount(amount, self.clock.today())
+  }
+
+  /// Same as [`ChristmasDiscount::apply_discount`] but it is Christmas when
+  /// it is December 25 in `time_zone`, an IANA name such as "Asia/Tokyo".
+  pub fn apply_discount_in(
+    &self,
+    amount: &Money,
+    time_zone: &str,
+  ) -> Result<Money, UnknownTimeZone> {
+    Ok(self.discount(amount, self.clock.today_in(clock::time_zone(time_zone)?)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::MockClock;
+  use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+  use std::cell::Cell;
+
+  fn usd(amount: &str) -> Money {
+    format!("{} USD", amount).parse().unwrap()
+  }
+
+  fn today(month: u32, day: u32) -> Today {
+    Today {
+      year: 2021,
+      month,
+      day,
+      weekday: Weekday::Fri,
+      time: NaiveTime::from_hms(12, 0, 0),
+    }
+  }
+
+  #[test]
+  fn does_not_apply_discount_if_today_is_not_christmas() {
+    let mut clock = MockClock::new();
+
+    clock.expect_today().return_const(today(12, 24));
+
+    let sut = ChristmasDiscount::new(clock);
+
+    assert_eq!(usd("10.00"), sut.apply_discount(&usd("10.00")));
+  }
+
+  #[test]
+  fn applies_discount_if_today_is_christmas() {
+    let mut clock = MockClock::new();
+
+    clock.expect_today().return_const(today(12, 25));
+
+    let sut = ChristmasDiscount::new(clock);
+    assert_eq!(usd("85.00"), sut.apply_discount(&usd("100.00")));
+  }
+
+  #[test]
+  fn discount_follows_a_manual_clock() {
+    let clock = ManualClock::new(Utc.ymd(2021, 12, 24).and_hms(23, 59, 59));
+
+    let sut = ChristmasDiscount::new(clock.clone());
+    assert_eq!(usd("100.00"), sut.apply_discount(&usd("100.00")));
+
+    clock.advance(Duration::seconds(1));
+    assert_eq!(usd("85.00"), sut.apply_discount(&usd("100.00")));
+
+    clock.set(Utc.ymd(2021, 12, 26).and_hms(0, 0, 0));
+    assert_eq!(usd("100.00"), sut.apply_discount(&usd("100.00")));
+  }
+
+  #[test]
+  fn discount_with_a_fixed_clock() {
+    let sut = ChristmasDiscount::new(FixedClock::new(Utc.ymd(2021, 12, 25).and_hms(8, 0, 0)));
+
+    assert_eq!(usd("85.00"), sut.apply_discount(&usd("100.00")));
+  }
+
+  #[test]
+  fn reads_the_clock_once() {
+    /// Every reading is one second after the previous one.
+    struct TickingClock {
+      now: Cell<DateTime<Utc>>,
+    }
+
+    impl Clock for TickingClock {
+      fn now(&self) -> DateTime<Utc> {
+        let now = self.now.get();
+        self.now.set(now + Duration::seconds(1));
+        now
+      }
+    }
+
+    // Reading the month and then the day would see November 30 and then
+    // December 1, which together make November 1.
+    let clock = TickingClock {
+      now: Cell::new(Utc.ymd(2021, 11, 30).and_hms(23, 59, 59)),
+    };
+
+    assert_eq!(NaiveDate::from_ymd(2021, 11, 30), clock.today().date());
+
+    let sut = ChristmasDiscount::new(clock);
+
+    assert_eq!(usd("100.00"), sut.apply_discount(&usd("100.00")));
+    assert_eq!(
+      Utc.ymd(2021, 12, 1).and_hms(0, 0, 1),
+      sut.clock.now(),
+      "apply_discount should read the clock once"
+    );
+  }
+
+  #[test]
+  fn christmas_in_the_time_zone_of_the_customer() {
+    // It is still December 24 in UTC but already Christmas in Tokyo.
+    let sut = ChristmasDiscount::new(FixedClock::new(Utc.ymd(2021, 12, 24).and_hms(20, 0, 0)));
+
+    assert_eq!(usd("100.00"), sut.apply_discount(&usd("100.00")));
+    assert_eq!(
+      Ok(usd("85.00")),
+      sut.apply_discount_in(&usd("100.00"), "Asia/Tokyo")
+    );
+    assert_eq!(
+      Ok(usd("100.00")),
+      sut.apply_discount_in(&usd("100.00"), "America/Los_Angeles")
+    );
+
+    // It is already December 26 in UTC but still Christmas in Los Angeles.
+    let sut = ChristmasDiscount::new(FixedClock::new(Utc.ymd(2021, 12, 26).and_hms(5, 0, 0)));
+
+    assert_eq!(usd("100.00"), sut.apply_discount(&usd("100.00")));
+    assert_eq!(
+      Ok(usd("100.00")),
+      sut.apply_discount_in(&usd("100.00"), "Asia/Tokyo")
+    );
+    assert_eq!(
+      Ok(usd("85.00")),
+      sut.apply_discount_in(&usd("100.00"), "America/Los_Angeles")
+    );
+  }
+
+  #[test]
+  fn christmas_on_both_sides_of_the_date_line() {
+    let clock = ManualClock::new(Utc.ymd(2021, 12, 24).and_hms(10, 0, 0));
+
+    let sut = ChristmasDiscount::new(clock.clone());
+
+    // Christmas starts in UTC+14 while it is still December 23 in UTC-11.
+    assert_eq!(
+      Ok(usd("85.00")),
+      sut.apply_discount_in(&usd("100.00"), "Pacific/Kiritimati")
+    );
+    assert_eq!(
+      Ok(usd("100.00")),
+      sut.apply_discount_in(&usd("100.00"), "Pacific/Pago_Pago")
+    );
+
+    // Christmas ends in UTC+14 right when it starts in UTC-11.
+    clock.advance(Duration::days(1) + Duration::hours(1));
+    assert_eq!(
+      Ok(usd("100.00")),
+      sut.apply_discount_in(&usd("100.00"), "Pacific/Kiritimati")
+    );
+    assert_eq!(
+      Ok(usd("85.00")),
+      sut.apply_discount_in(&usd("100.00"), "Pacific/Pago_Pago")
+    );
+  }
+
+  #[test]
+  fn unknown_time_zone() {
+    let sut = ChristmasDiscount::new(FixedClock::new(Utc.ymd(2021, 12, 25).and_hms(12, 0, 0)));
+
+    assert_eq!(
+      Err(UnknownTimeZone("Europe/Atlantis".to_string())),
+      sut.apply_discount_in(&usd("100.00"), "Europe/Atlantis")
+    );
+  }
+
+  #[test]
+  fn configurable_discount_and_rounding() {
+    let christmas = || FixedClock::new(Utc.ymd(2021, 12, 25).and_hms(12, 0, 0));
+
+    // 0.85 * 0.10 = 0.085
+    assert_eq!(
+      usd("0.08"),
+      ChristmasDiscount::new(christmas()).apply_discount(&usd("0.10"))
+    );
+    assert_eq!(
+      usd("0.09"),
+      ChristmasDiscount::new(christmas())
+        .with_rounding(RoundingMode::HalfUp)
+        .apply_discount(&usd("0.10"))
+    );
+    assert_eq!(
+      usd("0.08"),
+      ChristmasDiscount::new(christmas())
+        .with_rounding(RoundingMode::Floor)
+        .apply_discount(&usd("0.10"))
+    );
+
+    // 12.5% of 19.99 is 2.49875.
+    assert_eq!(
+      usd("17.49"),
+      ChristmasDiscount::new(christmas())
+        .with_discount(BasisPoints::new(1250).unwrap())
+        .apply_discount(&usd("19.99"))
+    );
+
+    // Amounts are kept in their currency.
+    assert_eq!(
+      "850 JPY",
+      ChristmasDiscount::new(christmas())
+        .apply_discount(&"1000 JPY".parse().unwrap())
+        .to_string()
+    );
+  }
+
+  #[test]
+  fn amounts_have_the_same_scale_on_every_day() {
+    let christmas = FixedClock::new(Utc.ymd(2021, 12, 25).and_hms(12, 0, 0));
+    let day_after = FixedClock::new(Utc.ymd(2021, 12, 26).and_hms(12, 0, 0));
+
+    assert_eq!(
+      "8.50 USD",
+      ChristmasDiscount::new(christmas)
+        .apply_discount(&usd("9.999"))
+        .to_string()
+    );
+    assert_eq!(
+      "10.00 USD",
+      ChristmasDiscount::new(day_after)
+        .apply_discount(&usd("9.999"))
+        .to_string()
+    );
+    assert_eq!(
+      "9.99 USD",
+      ChristmasDiscount::new(day_after)
+        .with_rounding(RoundingMode::Floor)
+        .apply_discount(&usd("9.999"))
+        .to_string()
+    );
+  }
+}
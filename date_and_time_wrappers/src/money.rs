@@ -0,0 +1,366 @@
+//! Exact amounts of money and percentages.
+//!
+//! Amounts are decimals, not floats, so applying a discount gives the same
+//! result on every platform.
+
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::{BigDecimal, Signed, Zero};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Currency {
+  Usd,
+  Eur,
+  Gbp,
+  Jpy,
+}
+
+impl Currency {
+  /// Number of digits after the decimal point of the smallest unit, such as
+  /// cents.
+  pub fn minor_units(&self) -> i64 {
+    match self {
+      Currency::Usd | Currency::Eur | Currency::Gbp => 2,
+      Currency::Jpy => 0,
+    }
+  }
+
+  /// ISO 4217 code.
+  pub fn code(&self) -> &'static str {
+    match self {
+      Currency::Usd => "USD",
+      Currency::Eur => "EUR",
+      Currency::Gbp => "GBP",
+      Currency::Jpy => "JPY",
+    }
+  }
+}
+
+impl fmt::Display for Currency {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.code())
+  }
+}
+
+impl FromStr for Currency {
+  type Err = ParseMoneyError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "USD" => Ok(Currency::Usd),
+      "EUR" => Ok(Currency::Eur),
+      "GBP" => Ok(Currency::Gbp),
+      "JPY" => Ok(Currency::Jpy),
+      _ => Err(ParseMoneyError::UnknownCurrency(s.to_string())),
+    }
+  }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseMoneyError {
+  #[error("expected an amount followed by a currency such as \"12.50 USD\"")]
+  Malformed,
+  #[error("invalid amount {0:?}")]
+  InvalidAmount(String),
+  #[error("unknown currency {0:?}")]
+  UnknownCurrency(String),
+}
+
+/// How amounts that fall between two minor units are rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+  /// Ties go to the even neighbour, also known as banker's rounding.
+  #[default]
+  HalfEven,
+  /// Ties go away from zero.
+  HalfUp,
+  /// Always goes towards negative infinity.
+  Floor,
+}
+
+impl RoundingMode {
+  /// Rounds `value` to `scale` digits after the decimal point.
+  pub fn round(&self, value: &BigDecimal, scale: i64) -> BigDecimal {
+    let (digits, exponent) = value.as_bigint_and_exponent();
+
+    if exponent <= scale {
+      return value.with_scale(scale);
+    }
+
+    let divisor = match u32::try_from(i128::from(exponent) - i128::from(scale)) {
+      Ok(shift) => BigInt::from(10).pow(shift),
+      // The value would need more than four billion digits to reach half a
+      // unit of `scale`.
+      Err(_) => {
+        let rounded = match self {
+          RoundingMode::Floor if digits.is_negative() => -1,
+          _ => 0,
+        };
+
+        return BigDecimal::new(BigInt::from(rounded), scale);
+      }
+    };
+
+    // Both truncate towards zero.
+    let quotient = &digits / &divisor;
+    let remainder = &digits % &divisor;
+
+    if remainder.is_zero() {
+      return BigDecimal::new(quotient, scale);
+    }
+
+    let away_from_zero = if digits.is_negative() {
+      BigInt::from(-1)
+    } else {
+      BigInt::from(1)
+    };
+
+    let twice_remainder = remainder.abs() * 2;
+
+    let rounded = match self {
+      RoundingMode::Floor if digits.is_negative() => quotient - 1,
+      RoundingMode::Floor => quotient,
+      RoundingMode::HalfUp if twice_remainder >= divisor => quotient + away_from_zero,
+      RoundingMode::HalfUp => quotient,
+      RoundingMode::HalfEven if twice_remainder > divisor => quotient + away_from_zero,
+      RoundingMode::HalfEven
+        if twice_remainder == divisor && (&quotient % 2u32) != BigInt::zero() =>
+      {
+        quotient + away_from_zero
+      }
+      RoundingMode::HalfEven => quotient,
+    };
+
+    BigDecimal::new(rounded, scale)
+  }
+}
+
+/// An exact amount of a currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Money {
+  amount: BigDecimal,
+  currency: Currency,
+}
+
+impl Money {
+  pub fn new(amount: BigDecimal, currency: Currency) -> Self {
+    Self { amount, currency }
+  }
+
+  pub fn amount(&self) -> &BigDecimal {
+    &self.amount
+  }
+
+  pub fn currency(&self) -> Currency {
+    self.currency
+  }
+
+  /// Takes `discount` off the amount and rounds the result to the minor
+  /// unit of the currency.
+  pub fn discounted(&self, discount: BasisPoints, rounding: RoundingMode) -> Self {
+    // Basis points are ten-thousandths.
+    let remaining = BigDecimal::new(BigInt::from(BasisPoints::MAX - discount.0), 4);
+
+    Self {
+      amount: rounding.round(&(&self.amount * remaining), self.currency.minor_units()),
+      currency: self.currency,
+    }
+  }
+}
+
+impl fmt::Display for Money {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.amount, self.currency)
+  }
+}
+
+impl FromStr for Money {
+  type Err = ParseMoneyError;
+
+  /// Parses an amount followed by a currency code, such as "12.50 USD".
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (amount, currency) = s.trim().split_once(' ').ok_or(ParseMoneyError::Malformed)?;
+
+    Ok(Self {
+      amount: BigDecimal::from_str(amount)
+        .map_err(|_| ParseMoneyError::InvalidAmount(amount.to_string()))?,
+      currency: currency.trim().parse()?,
+    })
+  }
+}
+
+/// A percentage in hundredths of a percent: 1500 basis points are 15%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BasisPoints(u32);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("basis points must be between 0 and 10000. got {0}")]
+pub struct InvalidBasisPoints(pub u32);
+
+impl BasisPoints {
+  /// 100%.
+  pub const MAX: u32 = 10_000;
+
+  pub fn new(basis_points: u32) -> Result<Self, InvalidBasisPoints> {
+    if basis_points > Self::MAX {
+      return Err(InvalidBasisPoints(basis_points));
+    }
+
+    Ok(Self(basis_points))
+  }
+
+  pub fn get(&self) -> u32 {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn money(s: &str) -> Money {
+    s.parse().unwrap()
+  }
+
+  fn bp(basis_points: u32) -> BasisPoints {
+    BasisPoints::new(basis_points).unwrap()
+  }
+
+  fn round(value: &str, mode: RoundingMode) -> String {
+    mode
+      .round(&BigDecimal::from_str(value).unwrap(), 0)
+      .to_string()
+  }
+
+  #[test]
+  fn rounding_modes() {
+    let cases = [
+      // value, half even, half up, floor
+      ("2.5", "2", "3", "2"),
+      ("3.5", "4", "4", "3"),
+      ("2.4", "2", "2", "2"),
+      ("2.6", "3", "3", "2"),
+      ("-2.5", "-2", "-3", "-3"),
+      ("-2.4", "-2", "-2", "-3"),
+      ("-3.5", "-4", "-4", "-4"),
+      ("7", "7", "7", "7"),
+      ("2.50000000000000000000001", "3", "3", "2"),
+    ];
+
+    for (value, half_even, half_up, floor) in cases {
+      assert_eq!(half_even, round(value, RoundingMode::HalfEven), "{}", value);
+      assert_eq!(half_up, round(value, RoundingMode::HalfUp), "{}", value);
+      assert_eq!(floor, round(value, RoundingMode::Floor), "{}", value);
+    }
+  }
+
+  #[test]
+  fn rounds_values_far_below_the_scale() {
+    let tiny = "1E-4294967298";
+
+    assert_eq!("0", round(tiny, RoundingMode::HalfEven));
+    assert_eq!("0", round(tiny, RoundingMode::HalfUp));
+    assert_eq!("0", round(tiny, RoundingMode::Floor));
+    assert_eq!("-1", round(&format!("-{}", tiny), RoundingMode::Floor));
+    assert_eq!("0", round(&format!("-{}", tiny), RoundingMode::HalfUp));
+
+    assert_eq!(
+      "0.00 USD",
+      money("1E-4294967298 USD")
+        .discounted(bp(1500), RoundingMode::HalfEven)
+        .to_string()
+    );
+    assert_eq!(
+      "-0.01 USD",
+      money("-1E-4294967298 USD")
+        .discounted(bp(1500), RoundingMode::Floor)
+        .to_string()
+    );
+  }
+
+  #[test]
+  fn rounds_to_a_scale() {
+    assert_eq!(
+      "1.24",
+      RoundingMode::HalfEven
+        .round(&BigDecimal::from_str("1.235").unwrap(), 2)
+        .to_string()
+    );
+    assert_eq!(
+      "1.50",
+      RoundingMode::HalfEven
+        .round(&BigDecimal::from_str("1.5").unwrap(), 2)
+        .to_string()
+    );
+  }
+
+  #[test]
+  fn discounts() {
+    assert_eq!(
+      money("85.00 USD"),
+      money("100.00 USD").discounted(bp(1500), RoundingMode::HalfEven)
+    );
+    assert_eq!(
+      money("0.00 USD"),
+      money("100.00 USD").discounted(bp(10_000), RoundingMode::HalfEven)
+    );
+    assert_eq!(
+      money("100.00 USD"),
+      money("100.00 USD").discounted(bp(0), RoundingMode::HalfEven)
+    );
+
+    // 0.85 * 0.10 = 0.085
+    assert_eq!(
+      money("0.08 USD"),
+      money("0.10 USD").discounted(bp(1500), RoundingMode::HalfEven)
+    );
+    assert_eq!(
+      money("0.09 USD"),
+      money("0.10 USD").discounted(bp(1500), RoundingMode::HalfUp)
+    );
+    assert_eq!(
+      money("0.08 USD"),
+      money("0.10 USD").discounted(bp(1500), RoundingMode::Floor)
+    );
+
+    // Yen have no minor unit: 0.85 * 999 = 849.15
+    assert_eq!(
+      money("849 JPY"),
+      money("999 JPY").discounted(bp(1500), RoundingMode::HalfUp)
+    );
+
+    // 0.01% of a cent is still rounded to cents.
+    assert_eq!(
+      "9.99 EUR",
+      money("9.99 EUR")
+        .discounted(bp(1), RoundingMode::HalfEven)
+        .to_string()
+    );
+  }
+
+  #[test]
+  fn parsing() {
+    assert_eq!(
+      Money::new(BigDecimal::from_str("12.50").unwrap(), Currency::Gbp),
+      money("12.50 GBP")
+    );
+    assert_eq!(Err(ParseMoneyError::Malformed), "12.50".parse::<Money>());
+    assert_eq!(
+      Err(ParseMoneyError::InvalidAmount("twelve".to_string())),
+      "twelve USD".parse::<Money>()
+    );
+    assert_eq!(
+      Err(ParseMoneyError::UnknownCurrency("XXX".to_string())),
+      "12 XXX".parse::<Money>()
+    );
+  }
+
+  #[test]
+  fn basis_points() {
+    assert_eq!(Ok(1500), BasisPoints::new(1500).map(|bp| bp.get()));
+    assert_eq!(Err(InvalidBasisPoints(10_001)), BasisPoints::new(10_001));
+  }
+}
@@ -0,0 +1,329 @@
+//! Promotions that are valid from one instant to another, such as a Black
+//! Friday weekend or a two hour flash sale, and how to resolve promotions
+//! whose windows overlap.
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{BasisPoints, Clock, Money, RoundingMode};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("promotion {name:?} ends at {ends_at} which is not after it starts at {starts_at}")]
+pub struct InvalidWindow {
+  pub name: String,
+  pub starts_at: DateTime<Utc>,
+  pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Promotion {
+  name: String,
+  starts_at: DateTime<Utc>,
+  ends_at: DateTime<Utc>,
+  discount: BasisPoints,
+  priority: u32,
+}
+
+impl Promotion {
+  /// A promotion that is active from `starts_at`, included, to `ends_at`,
+  /// excluded. Its priority is 0.
+  pub fn new(
+    name: impl Into<String>,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    discount: BasisPoints,
+  ) -> Result<Self, InvalidWindow> {
+    let name = name.into();
+
+    if ends_at <= starts_at {
+      return Err(InvalidWindow {
+        name,
+        starts_at,
+        ends_at,
+      });
+    }
+
+    Ok(Self {
+      name,
+      starts_at,
+      ends_at,
+      discount,
+      priority: 0,
+    })
+  }
+
+  /// Promotions with a higher priority win with [`OverlapPolicy::HighestPriority`].
+  pub fn with_priority(mut self, priority: u32) -> Self {
+    self.priority = priority;
+    self
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn discount(&self) -> BasisPoints {
+    self.discount
+  }
+
+  pub fn priority(&self) -> u32 {
+    self.priority
+  }
+
+  pub fn is_active_at(&self, instant: DateTime<Utc>) -> bool {
+    self.starts_at <= instant && instant < self.ends_at
+  }
+}
+
+/// What to do when several promotions are active at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+  /// Only the promotion with the biggest discount applies.
+  BestForCustomer,
+  /// Only the promotion with the highest priority applies. Ties go to the
+  /// biggest discount.
+  HighestPriority,
+  /// Every promotion applies, and their discounts are added up to `cap`.
+  Stack { cap: BasisPoints },
+}
+
+pub struct Promotions<C: Clock> {
+  clock: C,
+  policy: OverlapPolicy,
+  rounding: RoundingMode,
+  promotions: Vec<Promotion>,
+}
+
+impl<C: Clock> Promotions<C> {
+  pub fn new(clock: C, policy: OverlapPolicy) -> Self {
+    Self {
+      clock,
+      policy,
+      rounding: RoundingMode::default(),
+      promotions: vec![],
+    }
+  }
+
+  pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+    self.rounding = rounding;
+    self
+  }
+
+  pub fn add(&mut self, promotion: Promotion) {
+    self.promotions.push(promotion);
+  }
+
+  /// The promotions that are active now, in the order they were added.
+  pub fn active(&self) -> Vec<&Promotion> {
+    let now = self.clock.now();
+
+    self
+      .promotions
+      .iter()
+      .filter(|promotion| promotion.is_active_at(now))
+      .collect()
+  }
+
+  /// The active promotions that apply according to the overlap policy.
+  pub fn applied(&self) -> Vec<&Promotion> {
+    let active = self.active();
+
+    // `max_by_key` returns the last maximum, so iterating in reverse makes
+    // the promotion that was added first win a tie.
+    let winner = match self.policy {
+      OverlapPolicy::Stack { .. } => return active,
+      OverlapPolicy::BestForCustomer => active
+        .into_iter()
+        .rev()
+        .max_by_key(|promotion| promotion.discount),
+      OverlapPolicy::HighestPriority => active
+        .into_iter()
+        .rev()
+        .max_by_key(|promotion| (promotion.priority, promotion.discount)),
+    };
+
+    winner.into_iter().collect()
+  }
+
+  /// The discount of the promotions that apply now.
+  pub fn discount(&self) -> BasisPoints {
+    let total: u32 = self
+      .applied()
+      .iter()
+      .map(|promotion| promotion.discount.get())
+      .sum();
+
+    let total = match self.policy {
+      OverlapPolicy::Stack { cap } => total.min(cap.get()),
+      _ => total,
+    };
+
+    BasisPoints::new(total.min(BasisPoints::MAX)).unwrap()
+  }
+
+  pub fn apply_discount(&self, amount: &Money) -> Money {
+    amount.discounted(self.discount(), self.rounding)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::clock::time_zone;
+  use crate::ManualClock;
+  use chrono::{Duration, TimeZone};
+
+  fn usd(amount: &str) -> Money {
+    format!("{} USD", amount).parse().unwrap()
+  }
+
+  fn bp(basis_points: u32) -> BasisPoints {
+    BasisPoints::new(basis_points).unwrap()
+  }
+
+  fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.ymd(2021, 11, day).and_hms(hour, minute, 0)
+  }
+
+  fn names<C: Clock>(promotions: &Promotions<C>) -> Vec<&str> {
+    promotions
+      .applied()
+      .into_iter()
+      .map(|promotion| promotion.name())
+      .collect()
+  }
+
+  /// Black Friday weekend at 20% and a flash sale at 30% on Saturday from
+  /// 14:00 to 16:00.
+  fn promotions(clock: ManualClock, policy: OverlapPolicy) -> Promotions<ManualClock> {
+    let mut promotions = Promotions::new(clock, policy);
+
+    promotions.add(
+      Promotion::new("Black Friday", at(26, 0, 0), at(29, 0, 0), bp(2000))
+        .unwrap()
+        .with_priority(2),
+    );
+    promotions.add(
+      Promotion::new("Flash sale", at(27, 14, 0), at(27, 16, 0), bp(3000))
+        .unwrap()
+        .with_priority(1),
+    );
+
+    promotions
+  }
+
+  #[test]
+  fn windows_include_their_start_and_exclude_their_end() {
+    let clock = ManualClock::new(at(25, 23, 59));
+
+    let promotions = promotions(clock.clone(), OverlapPolicy::BestForCustomer);
+    assert_eq!(usd("100.00"), promotions.apply_discount(&usd("100.00")));
+
+    clock.advance(Duration::minutes(1));
+    assert_eq!(usd("80.00"), promotions.apply_discount(&usd("100.00")));
+
+    clock.set(at(28, 23, 59));
+    assert_eq!(usd("80.00"), promotions.apply_discount(&usd("100.00")));
+
+    clock.advance(Duration::minutes(1));
+    assert_eq!(usd("100.00"), promotions.apply_discount(&usd("100.00")));
+    assert!(promotions.active().is_empty());
+  }
+
+  #[test]
+  fn best_for_customer() {
+    let clock = ManualClock::new(at(27, 13, 59));
+
+    let promotions = promotions(clock.clone(), OverlapPolicy::BestForCustomer);
+    assert_eq!(vec!["Black Friday"], names(&promotions));
+
+    clock.advance(Duration::minutes(1));
+    assert_eq!(vec!["Flash sale"], names(&promotions));
+    assert_eq!(
+      vec!["Black Friday", "Flash sale"],
+      promotions
+        .active()
+        .into_iter()
+        .map(|promotion| promotion.name())
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(usd("70.00"), promotions.apply_discount(&usd("100.00")));
+
+    clock.set(at(27, 16, 0));
+    assert_eq!(vec!["Black Friday"], names(&promotions));
+  }
+
+  #[test]
+  fn highest_priority() {
+    let clock = ManualClock::new(at(27, 15, 0));
+
+    let promotions = promotions(clock, OverlapPolicy::HighestPriority);
+
+    assert_eq!(vec!["Black Friday"], names(&promotions));
+    assert_eq!(usd("80.00"), promotions.apply_discount(&usd("100.00")));
+  }
+
+  #[test]
+  fn stacking_with_a_cap() {
+    let clock = ManualClock::new(at(27, 15, 0));
+
+    let capped = promotions(clock.clone(), OverlapPolicy::Stack { cap: bp(4000) });
+    assert_eq!(vec!["Black Friday", "Flash sale"], names(&capped));
+    assert_eq!(bp(4000), capped.discount());
+    assert_eq!(usd("60.00"), capped.apply_discount(&usd("100.00")));
+
+    let uncapped = promotions(clock.clone(), OverlapPolicy::Stack { cap: bp(10_000) });
+    assert_eq!(usd("50.00"), uncapped.apply_discount(&usd("100.00")));
+
+    clock.set(at(27, 16, 0));
+    assert_eq!(usd("80.00"), capped.apply_discount(&usd("100.00")));
+  }
+
+  #[test]
+  fn ties_go_to_the_promotion_added_first() {
+    let clock = ManualClock::new(at(27, 15, 0));
+
+    let mut promotions = Promotions::new(clock, OverlapPolicy::BestForCustomer);
+    promotions.add(Promotion::new("First", at(27, 0, 0), at(28, 0, 0), bp(1000)).unwrap());
+    promotions.add(Promotion::new("Second", at(27, 0, 0), at(28, 0, 0), bp(1000)).unwrap());
+
+    assert_eq!(vec!["First"], names(&promotions));
+  }
+
+  #[test]
+  fn windows_in_local_time() {
+    // A flash sale from 14:00 to 16:00 in New York, which is UTC-5 in November.
+    let new_york = time_zone("America/New_York").unwrap();
+
+    let flash_sale = Promotion::new(
+      "Flash sale",
+      new_york
+        .ymd(2021, 11, 27)
+        .and_hms(14, 0, 0)
+        .with_timezone(&Utc),
+      new_york
+        .ymd(2021, 11, 27)
+        .and_hms(16, 0, 0)
+        .with_timezone(&Utc),
+      bp(3000),
+    )
+    .unwrap();
+
+    assert!(!flash_sale.is_active_at(at(27, 14, 0)));
+    assert!(flash_sale.is_active_at(at(27, 19, 0)));
+    assert!(!flash_sale.is_active_at(at(27, 21, 0)));
+  }
+
+  #[test]
+  fn invalid_windows() {
+    assert_eq!(
+      Err(InvalidWindow {
+        name: "Backwards".to_string(),
+        starts_at: at(27, 16, 0),
+        ends_at: at(27, 14, 0),
+      }),
+      Promotion::new("Backwards", at(27, 16, 0), at(27, 14, 0), bp(1000))
+    );
+    assert!(Promotion::new("Empty", at(27, 16, 0), at(27, 16, 0), bp(1000)).is_err());
+  }
+}
@@ -0,0 +1,404 @@
+//! Runs callbacks at a given instant or at a recurring interval.
+//!
+//! The scheduler reads the time from a [`Clock`]. With a [`ManualClock`],
+//! [`Scheduler::advance`] moves the time and fires every timer that becomes
+//! due, one after the other, so tests are deterministic. With a
+//! [`SystemClock`], [`Scheduler::run_until`] sleeps until the next timer is due.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::thread;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{Clock, ManualClock, SystemClock};
+
+/// Identifies a timer to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(u64);
+
+/// Called with the instant the timer was due at.
+pub type Callback = Box<dyn FnMut(DateTime<Utc>)>;
+
+struct Timer {
+  every: Option<Duration>,
+  callback: Callback,
+}
+
+pub struct Scheduler<C: Clock> {
+  clock: C,
+  timers: HashMap<TimerId, Timer>,
+  /// When each timer is due. Timers that are due at the same instant fire in
+  /// the order they were scheduled, which is the order of the sequence number.
+  /// Entries of cancelled timers are skipped when they are popped.
+  queue: BinaryHeap<Reverse<(DateTime<Utc>, u64, TimerId)>>,
+  next_sequence: u64,
+  next_id: u64,
+}
+
+impl<C: Clock> Scheduler<C> {
+  pub fn new(clock: C) -> Self {
+    Self {
+      clock,
+      timers: HashMap::new(),
+      queue: BinaryHeap::new(),
+      next_sequence: 0,
+      next_id: 0,
+    }
+  }
+
+  pub fn clock(&self) -> &C {
+    &self.clock
+  }
+
+  /// Calls `callback` once at `at`. Instants in the past are due right away.
+  pub fn schedule_at<F>(&mut self, at: DateTime<Utc>, callback: F) -> TimerId
+  where
+    F: FnMut(DateTime<Utc>) + 'static,
+  {
+    self.schedule(at, None, Box::new(callback))
+  }
+
+  /// Calls `callback` once after `delay`.
+  pub fn schedule_after<F>(&mut self, delay: Duration, callback: F) -> TimerId
+  where
+    F: FnMut(DateTime<Utc>) + 'static,
+  {
+    self.schedule_at(self.clock.now() + delay, callback)
+  }
+
+  /// Calls `callback` every `interval`, starting one `interval` from now,
+  /// until the timer is cancelled.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `interval` is not positive.
+  pub fn schedule_every<F>(&mut self, interval: Duration, callback: F) -> TimerId
+  where
+    F: FnMut(DateTime<Utc>) + 'static,
+  {
+    assert!(
+      interval > Duration::zero(),
+      "interval must be positive. got {}",
+      interval
+    );
+
+    self.schedule(
+      self.clock.now() + interval,
+      Some(interval),
+      Box::new(callback),
+    )
+  }
+
+  /// Returns `false` if the timer already fired, for one-shot timers, or was
+  /// already cancelled.
+  pub fn cancel(&mut self, id: TimerId) -> bool {
+    self.timers.remove(&id).is_some()
+  }
+
+  /// Number of timers that will fire again.
+  pub fn pending(&self) -> usize {
+    self.timers.len()
+  }
+
+  /// When the next timer is due.
+  pub fn next_due(&mut self) -> Option<DateTime<Utc>> {
+    self.skip_cancelled();
+
+    self.queue.peek().map(|Reverse((due, _, _))| *due)
+  }
+
+  /// Fires every timer that is due now, earliest first, and returns how many
+  /// callbacks were called. A recurring timer that missed several intervals
+  /// fires once for each of them.
+  pub fn run_due(&mut self) -> usize {
+    let now = self.clock.now();
+
+    let mut fired = 0;
+
+    while self.fire_next(now) {
+      fired += 1;
+    }
+
+    fired
+  }
+
+  fn schedule(
+    &mut self,
+    at: DateTime<Utc>,
+    every: Option<Duration>,
+    callback: Callback,
+  ) -> TimerId {
+    let id = TimerId(self.next_id);
+    self.next_id += 1;
+
+    self.timers.insert(id, Timer { every, callback });
+    self.enqueue(at, id);
+
+    id
+  }
+
+  fn enqueue(&mut self, at: DateTime<Utc>, id: TimerId) {
+    self.queue.push(Reverse((at, self.next_sequence, id)));
+    self.next_sequence += 1;
+  }
+
+  fn skip_cancelled(&mut self) {
+    while let Some(Reverse((_, _, id))) = self.queue.peek() {
+      if self.timers.contains_key(id) {
+        return;
+      }
+
+      self.queue.pop();
+    }
+  }
+
+  /// Fires the earliest timer if it is due at or before `until`.
+  fn fire_next(&mut self, until: DateTime<Utc>) -> bool {
+    match self.next_due() {
+      Some(due) if due <= until => {}
+      _ => return false,
+    }
+
+    let Reverse((due, _, id)) = self.queue.pop().expect("next_due returned a timer");
+
+    let timer = self
+      .timers
+      .get_mut(&id)
+      .expect("cancelled timers are skipped");
+
+    (timer.callback)(due);
+
+    let every = timer.every;
+
+    match every {
+      Some(interval) => self.enqueue(due + interval, id),
+      None => {
+        self.timers.remove(&id);
+      }
+    }
+
+    true
+  }
+}
+
+impl Scheduler<ManualClock> {
+  /// Moves the clock forward by `duration`, firing the timers that become
+  /// due on the way. The clock is at the due instant of each timer while its
+  /// callback runs.
+  pub fn advance(&mut self, duration: Duration) -> usize {
+    self.advance_to(self.clock.now() + duration)
+  }
+
+  /// Same as [`Scheduler::advance`] but moves the clock to `instant`.
+  pub fn advance_to(&mut self, instant: DateTime<Utc>) -> usize {
+    // Timers that were already due fire at the current time.
+    let mut fired = self.run_due();
+
+    while let Some(due) = self.next_due().filter(|due| *due <= instant) {
+      self.clock.set(due);
+
+      fired += self.run_due();
+    }
+
+    if self.clock.now() < instant {
+      self.clock.set(instant);
+    }
+
+    fired
+  }
+}
+
+impl Scheduler<SystemClock> {
+  /// Sleeps until each timer is due and fires it, until `deadline`.
+  pub fn run_until(&mut self, deadline: DateTime<Utc>) -> usize {
+    let mut fired = 0;
+
+    loop {
+      fired += self.run_due();
+
+      let now = self.clock.now();
+
+      if now >= deadline {
+        return fired;
+      }
+
+      let wake_up = self.next_due().map_or(deadline, |due| due.min(deadline));
+
+      if let Ok(duration) = (wake_up - now).to_std() {
+        thread::sleep(duration);
+      }
+    }
+  }
+
+  /// Same as [`Scheduler::run_until`] but runs for `duration`.
+  pub fn run_for(&mut self, duration: Duration) -> usize {
+    self.run_until(self.clock.now() + duration)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::TimeZone;
+  use std::cell::RefCell;
+  use std::rc::Rc;
+
+  type Log = Rc<RefCell<Vec<(&'static str, DateTime<Utc>)>>>;
+
+  fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.ymd(2021, 12, 24).and_hms(hour, minute, 0)
+  }
+
+  /// A callback that records its name and the instant it was due at.
+  fn record(log: &Log, name: &'static str) -> impl FnMut(DateTime<Utc>) {
+    let log = Rc::clone(log);
+
+    move |due| log.borrow_mut().push((name, due))
+  }
+
+  #[test]
+  fn fires_due_timers_in_order() {
+    let log = Log::default();
+
+    let mut scheduler = Scheduler::new(ManualClock::new(at(10, 0)));
+
+    scheduler.schedule_at(at(12, 0), record(&log, "noon"));
+    scheduler.schedule_at(at(11, 0), record(&log, "eleven"));
+    scheduler.schedule_after(Duration::hours(1), record(&log, "also eleven"));
+    scheduler.schedule_at(at(13, 0), record(&log, "one"));
+
+    assert_eq!(0, scheduler.advance(Duration::minutes(59)));
+    assert!(log.borrow().is_empty());
+
+    assert_eq!(3, scheduler.advance(Duration::hours(2)));
+    assert_eq!(
+      vec![
+        ("eleven", at(11, 0)),
+        ("also eleven", at(11, 0)),
+        ("noon", at(12, 0))
+      ],
+      *log.borrow()
+    );
+    assert_eq!(at(12, 59), scheduler.clock().now());
+    assert_eq!(1, scheduler.pending());
+  }
+
+  #[test]
+  fn recurring_timers() {
+    let log = Log::default();
+
+    let mut scheduler = Scheduler::new(ManualClock::new(at(10, 0)));
+
+    scheduler.schedule_every(Duration::minutes(30), record(&log, "every 30"));
+    scheduler.schedule_at(at(10, 45), record(&log, "once"));
+
+    assert_eq!(
+      4,
+      scheduler.advance(Duration::hours(1) + Duration::minutes(30))
+    );
+    assert_eq!(
+      vec![
+        ("every 30", at(10, 30)),
+        ("once", at(10, 45)),
+        ("every 30", at(11, 0)),
+        ("every 30", at(11, 30)),
+      ],
+      *log.borrow()
+    );
+    assert_eq!(Some(at(12, 0)), scheduler.next_due());
+  }
+
+  #[test]
+  fn callbacks_see_the_clock_at_their_due_instant() {
+    let clock = ManualClock::new(at(10, 0));
+
+    let seen = Rc::new(RefCell::new(vec![]));
+
+    let mut scheduler = Scheduler::new(clock.clone());
+
+    scheduler.schedule_every(Duration::hours(1), {
+      let seen = Rc::clone(&seen);
+      move |_| seen.borrow_mut().push(clock.now())
+    });
+
+    scheduler.advance(Duration::hours(2) + Duration::minutes(10));
+
+    assert_eq!(vec![at(11, 0), at(12, 0)], *seen.borrow());
+    assert_eq!(at(12, 10), scheduler.clock().now());
+  }
+
+  #[test]
+  fn cancelled_timers_do_not_fire() {
+    let log = Log::default();
+
+    let mut scheduler = Scheduler::new(ManualClock::new(at(10, 0)));
+
+    let once = scheduler.schedule_at(at(11, 0), record(&log, "once"));
+    let recurring = scheduler.schedule_every(Duration::minutes(15), record(&log, "recurring"));
+
+    assert!(scheduler.cancel(once));
+    assert!(!scheduler.cancel(once));
+
+    scheduler.advance(Duration::minutes(30));
+    assert!(scheduler.cancel(recurring));
+
+    scheduler.advance(Duration::hours(2));
+
+    assert_eq!(
+      vec![("recurring", at(10, 15)), ("recurring", at(10, 30))],
+      *log.borrow()
+    );
+    assert_eq!(0, scheduler.pending());
+    assert_eq!(None, scheduler.next_due());
+  }
+
+  #[test]
+  fn timers_in_the_past_are_due_right_away() {
+    let log = Log::default();
+
+    let clock = ManualClock::new(at(10, 0));
+
+    let mut scheduler = Scheduler::new(clock.clone());
+    scheduler.schedule_at(at(9, 0), record(&log, "late"));
+
+    // Moving the clock without the scheduler doesn't fire anything.
+    clock.advance(Duration::hours(1));
+    assert!(log.borrow().is_empty());
+
+    assert_eq!(1, scheduler.run_due());
+    assert_eq!(vec![("late", at(9, 0))], *log.borrow());
+  }
+
+  #[test]
+  #[should_panic(expected = "interval must be positive")]
+  fn recurring_timers_need_a_positive_interval() {
+    Scheduler::new(ManualClock::new(at(10, 0))).schedule_every(Duration::zero(), |_| {});
+  }
+
+  #[test]
+  fn system_clock_runs_real_timers() {
+    let log = Log::default();
+
+    let mut scheduler = Scheduler::new(SystemClock);
+
+    let start = SystemClock.now();
+
+    scheduler.schedule_after(Duration::milliseconds(40), record(&log, "second"));
+    scheduler.schedule_after(Duration::milliseconds(10), record(&log, "first"));
+    scheduler.schedule_after(Duration::seconds(60), record(&log, "never"));
+
+    assert_eq!(2, scheduler.run_for(Duration::milliseconds(80)));
+
+    assert!(SystemClock.now() - start >= Duration::milliseconds(80));
+    assert_eq!(
+      vec!["first", "second"],
+      log
+        .borrow()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(1, scheduler.pending());
+  }
+}
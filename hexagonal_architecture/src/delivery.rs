@@ -0,0 +1,121 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+
+use crate::ShoppingCart;
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+#[error("delivery center failed: {0}")]
+pub struct DeliveryError(pub String);
+
+#[cfg_attr(test, mockall::automock)]
+pub trait DeliveryCenter {
+  /// Starts the delivery of the cart and returns the estimated delivery day.
+  fn deliver(&self, cart: &ShoppingCart) -> Result<u8, DeliveryError>;
+}
+
+/// A delivery center that estimates the same delivery day for every cart,
+/// unless told otherwise, and records every cart it has to deliver.
+#[derive(Debug, Clone)]
+pub struct InMemoryDeliveryCenter {
+  estimated_delivery_day: u8,
+  state: Arc<Mutex<DeliveryState>>,
+}
+
+#[derive(Debug, Default)]
+struct DeliveryState {
+  estimates: HashMap<u64, u8>,
+  failing: HashSet<u64>,
+  delivered: Vec<ShoppingCart>,
+}
+
+impl InMemoryDeliveryCenter {
+  pub fn new(estimated_delivery_day: u8) -> Self {
+    Self {
+      estimated_delivery_day,
+      state: Arc::default(),
+    }
+  }
+
+  /// Estimates `estimated_delivery_day` for the cart with the given id.
+  pub fn with_estimate(self, cart_id: u64, estimated_delivery_day: u8) -> Self {
+    self
+      .state
+      .lock()
+      .unwrap()
+      .estimates
+      .insert(cart_id, estimated_delivery_day);
+
+    self
+  }
+
+  /// Makes the delivery of the cart with the given id fail.
+  pub fn fail_for(&self, cart_id: u64) {
+    self.state.lock().unwrap().failing.insert(cart_id);
+  }
+
+  /// Undoes [`InMemoryDeliveryCenter::fail_for`].
+  pub fn recover(&self) {
+    self.state.lock().unwrap().failing.clear();
+  }
+
+  /// Every cart successfully passed to [`DeliveryCenter::deliver`], in order.
+  pub fn delivered(&self) -> Vec<ShoppingCart> {
+    self.state.lock().unwrap().delivered.clone()
+  }
+}
+
+impl DeliveryCenter for InMemoryDeliveryCenter {
+  fn deliver(&self, cart: &ShoppingCart) -> Result<u8, DeliveryError> {
+    let mut state = self.state.lock().unwrap();
+
+    if state.failing.contains(&cart.id()) {
+      return Err(DeliveryError(format!("cannot deliver cart {}", cart.id())));
+    }
+
+    state.delivered.push(cart.clone());
+
+    Ok(
+      state
+        .estimates
+        .get(&cart.id())
+        .copied()
+        .unwrap_or(self.estimated_delivery_day),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn estimates_and_records_deliveries() {
+    let delivery_center = InMemoryDeliveryCenter::new(3).with_estimate(2, 5);
+
+    assert_eq!(Ok(3), delivery_center.deliver(&ShoppingCart::new(1)));
+    assert_eq!(
+      Ok(5),
+      delivery_center.clone().deliver(&ShoppingCart::new(2))
+    );
+
+    assert_eq!(
+      vec![ShoppingCart::new(1), ShoppingCart::new(2)],
+      delivery_center.delivered()
+    );
+  }
+
+  #[test]
+  fn failures_are_not_recorded() {
+    let delivery_center = InMemoryDeliveryCenter::new(3);
+
+    delivery_center.fail_for(1);
+
+    assert!(delivery_center.deliver(&ShoppingCart::new(1)).is_err());
+    assert!(delivery_center.delivered().is_empty());
+
+    delivery_center.recover();
+    assert_eq!(Ok(3), delivery_center.deliver(&ShoppingCart::new(1)));
+  }
+}
@@ -0,0 +1,291 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Mutex;
+
+use fs2::FileExt;
+
+use crate::outbox::{CartReadyForDelivery, Destination, Outbox, OutboxEntry};
+use crate::repositories::Store;
+use crate::{CartProgress, RepositoryError, ShoppinCartRepository, ShoppingCart};
+
+/// Stores the carts and the outbox in a single JSON file, so that the batch
+/// and the dispatcher can run without external infrastructure, even in
+/// different processes.
+///
+/// Every access holds an exclusive lock on a `<path>.lock` file next to it,
+/// so the read-modify-write cycles of different processes don't overwrite
+/// each other. Every write replaces the whole file by writing a temporary
+/// file of the process next to it and renaming it, so a crash leaves either
+/// the old or the new state.
+#[derive(Debug)]
+pub struct FileShoppinCartRepository {
+  path: PathBuf,
+  // Serializes the read-modify-write cycles of this process, the lock file
+  // serializes them across processes.
+  lock: Mutex<()>,
+}
+
+impl FileShoppinCartRepository {
+  /// Uses the file at `path`, which is created on the first write.
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self {
+      path: path.into(),
+      lock: Mutex::new(()),
+    }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Stores `cart` as a cart that was paid today.
+  pub fn add_paid_today(&self, cart: ShoppingCart) -> Result<(), RepositoryError> {
+    self.update(|store| {
+      store.add_paid_today(cart);
+      Ok(())
+    })
+  }
+
+  /// The current state of the cart with the given id.
+  pub fn get(&self, id: u64) -> Result<Option<ShoppingCart>, RepositoryError> {
+    self.read(|store| store.get(id).cloned())
+  }
+
+  /// Every entry of the outbox, dispatched or not, in order.
+  pub fn outbox(&self) -> Result<Vec<OutboxEntry>, RepositoryError> {
+    self.read(|store| store.outbox().to_vec())
+  }
+
+  fn read<T>(&self, f: impl FnOnce(&Store) -> T) -> Result<T, RepositoryError> {
+    let _guard = self.lock.lock().unwrap();
+    let _file_lock = self.lock_file()?;
+
+    Ok(f(&self.load()?))
+  }
+
+  fn update(
+    &self,
+    f: impl FnOnce(&mut Store) -> Result<(), RepositoryError>,
+  ) -> Result<(), RepositoryError> {
+    let _guard = self.lock.lock().unwrap();
+    let _file_lock = self.lock_file()?;
+
+    let mut store = self.load()?;
+    f(&mut store)?;
+    self.store(&store)
+  }
+
+  /// Blocks until no other process uses the file. The lock is released when
+  /// the returned file is dropped.
+  fn lock_file(&self) -> Result<File, RepositoryError> {
+    let file = OpenOptions::new()
+      .create(true)
+      .truncate(false)
+      .write(true)
+      .open(self.sibling(".lock"))
+      .map_err(|error| self.error(error))?;
+
+    file.lock_exclusive().map_err(|error| self.error(error))?;
+
+    Ok(file)
+  }
+
+  fn sibling(&self, suffix: &str) -> PathBuf {
+    let mut path = self.path.clone().into_os_string();
+    path.push(suffix);
+    PathBuf::from(path)
+  }
+
+  fn load(&self) -> Result<Store, RepositoryError> {
+    match File::open(&self.path) {
+      Ok(file) => serde_json::from_reader(file).map_err(|error| self.error(error)),
+      Err(error) if error.kind() == ErrorKind::NotFound => Ok(Store::default()),
+      Err(error) => Err(self.error(error)),
+    }
+  }
+
+  fn store(&self, store: &Store) -> Result<(), RepositoryError> {
+    let temporary = self.sibling(&format!(".{}.tmp", process::id()));
+
+    let write = || -> Result<(), Box<dyn std::error::Error>> {
+      let mut file = File::create(&temporary)?;
+
+      serde_json::to_writer(&mut file, store)?;
+      file.flush()?;
+      file.sync_all()?;
+
+      fs::rename(&temporary, &self.path)?;
+
+      Ok(())
+    };
+
+    write().map_err(|error| self.error(error))
+  }
+
+  fn error(&self, error: impl std::fmt::Display) -> RepositoryError {
+    RepositoryError(format!("{}: {}", self.path.display(), error))
+  }
+}
+
+impl ShoppinCartRepository for FileShoppinCartRepository {
+  fn get_carts_paid_today(&self) -> Result<Vec<ShoppingCart>, RepositoryError> {
+    self.read(Store::carts_paid_today)
+  }
+
+  fn save(
+    &self,
+    cart: &ShoppingCart,
+    events: Vec<CartReadyForDelivery>,
+  ) -> Result<(), RepositoryError> {
+    self.update(|store| {
+      store.save(cart, events);
+      Ok(())
+    })
+  }
+
+  fn progress(&self, cart_id: u64) -> Result<CartProgress, RepositoryError> {
+    self.read(|store| store.progress(cart_id))
+  }
+
+  fn record_delivery(
+    &self,
+    cart_id: u64,
+    estimated_delivery_day: u8,
+  ) -> Result<(), RepositoryError> {
+    self.update(|store| {
+      store.record_delivery(cart_id, estimated_delivery_day);
+      Ok(())
+    })
+  }
+}
+
+impl Outbox for FileShoppinCartRepository {
+  fn pending(&self) -> Result<Vec<OutboxEntry>, RepositoryError> {
+    self.read(Store::pending)
+  }
+
+  fn mark_dispatched(&self, id: u64, destination: Destination) -> Result<(), RepositoryError> {
+    self.update(|store| store.mark_dispatched(id, destination))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::env;
+
+  fn path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!(
+      "hexagonal_architecture-{}-{}.json",
+      name,
+      std::process::id()
+    ));
+
+    remove(&path);
+
+    path
+  }
+
+  fn remove(path: &Path) {
+    let _ = fs::remove_file(path);
+    let _ = fs::remove_file(FileShoppinCartRepository::new(path).sibling(".lock"));
+  }
+
+  #[test]
+  fn a_missing_file_is_empty() {
+    let repository = FileShoppinCartRepository::new(path("missing"));
+
+    assert_eq!(Ok(vec![]), repository.get_carts_paid_today());
+    assert_eq!(Ok(vec![]), repository.pending());
+
+    remove(repository.path());
+  }
+
+  #[test]
+  fn carts_and_outbox_survive_reopening() {
+    let path = path("reopen");
+    let repository = FileShoppinCartRepository::new(&path);
+
+    repository.add_paid_today(ShoppingCart::new(1)).unwrap();
+
+    let mut cart = ShoppingCart::new(1);
+    cart.mask_as_ready_for_delivery(3);
+    repository
+      .save(&cart, vec![CartReadyForDelivery { cart: cart.clone() }])
+      .unwrap();
+    repository
+      .mark_dispatched(1, Destination::Notifier)
+      .unwrap();
+
+    let reopened = FileShoppinCartRepository::new(&path);
+
+    assert_eq!(Ok(vec![cart.clone()]), reopened.get_carts_paid_today());
+    assert_eq!(
+      vec![Destination::Sap],
+      reopened.pending().unwrap()[0]
+        .pending
+        .iter()
+        .copied()
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(
+      Ok(CartProgress {
+        delivered: Some(3),
+        saved: true,
+        notified: true,
+        sap_updated: false,
+      }),
+      reopened.progress(1)
+    );
+    assert!(!reopened
+      .sibling(&format!(".{}.tmp", process::id()))
+      .exists());
+
+    remove(&path);
+  }
+
+  #[test]
+  fn a_corrupt_file_is_an_error() {
+    let path = path("corrupt");
+    fs::write(&path, "not json").unwrap();
+
+    let repository = FileShoppinCartRepository::new(&path);
+
+    assert!(repository.get_carts_paid_today().is_err());
+    assert!(repository.add_paid_today(ShoppingCart::new(1)).is_err());
+    assert_eq!("not json", fs::read_to_string(&path).unwrap());
+
+    remove(&path);
+  }
+
+  #[test]
+  fn concurrent_writers_do_not_lose_updates() {
+    let path = path("concurrent");
+
+    // Separate repositories don't share the in-process mutex, like the
+    // repositories of different processes.
+    std::thread::scope(|scope| {
+      for thread in 0..4_u64 {
+        let path = &path;
+
+        scope.spawn(move || {
+          let repository = FileShoppinCartRepository::new(path);
+
+          for i in 0..10 {
+            repository
+              .add_paid_today(ShoppingCart::new(thread * 10 + i))
+              .unwrap();
+          }
+        });
+      }
+    });
+
+    let repository = FileShoppinCartRepository::new(&path);
+
+    assert_eq!(40, repository.get_carts_paid_today().unwrap().len());
+
+    remove(&path);
+  }
+}
@@ -0,0 +1,356 @@
+//! For all the shopping carts that were paid today, the system should:
+//! Set the status of the shopping cart as "ready for delivery" and persist its
+//! new state in the database
+//! Notify the delivery center and let them know they should start working on
+//! sending the goods to the customer
+//! Notify the SAP system
+//! Send an e-mail to the customer, confirming that the payment was
+//! successful. The e-mail should contain an estimate of when the delivery
+//! will happen. The information is available via the delivery center API.
+//!
+//! Each external system is a port trait with an in-memory adapter that
+//! records the calls it receives, so the batch can be wired with fakes in
+//! tests and with real adapters in production.
+//!
+//! The e-mail and the SAP update go through a transactional [`outbox`]: the
+//! batch saves the cart together with a [`CartReadyForDelivery`] event, and
+//! the [`OutboxDispatcher`] delivers the event afterwards.
+
+pub mod delivery;
+pub mod file_repository;
+pub mod notifications;
+pub mod outbox;
+pub mod progress;
+pub mod report;
+pub mod repositories;
+pub mod sap;
+
+pub use delivery::{DeliveryCenter, DeliveryError, InMemoryDeliveryCenter};
+pub use file_repository::FileShoppinCartRepository;
+pub use notifications::{InMemoryNotifier, NotificationError, Notifier};
+pub use outbox::{CartReadyForDelivery, Destination, Outbox, OutboxDispatcher, OutboxEntry};
+pub use progress::CartProgress;
+pub use report::{
+  BatchReport, CartOutcome, DispatchError, DispatchOutcome, DispatchReport, Step, StepError,
+};
+pub use repositories::{InMemoryShoppinCartRepository, RepositoryError, ShoppinCartRepository};
+pub use sap::{InMemorySap, Sap, SapError};
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+#[derive(TypedBuilder)]
+pub struct PaidShoppingCartsBatch {
+  shopping_cart_repo: Box<dyn ShoppinCartRepository>,
+  delivery_center: Box<dyn DeliveryCenter>,
+}
+
+impl PaidShoppingCartsBatch {
+  /// Processes every cart paid today, carrying on with the next cart when one
+  /// fails.
+  ///
+  /// The steps a cart already went through in a previous run are skipped, so
+  /// running the batch again after a crash resumes where it stopped.
+  ///
+  /// Fails only if the carts paid today cannot be loaded.
+  pub fn process_all(&self) -> Result<BatchReport, RepositoryError> {
+    let mut report = BatchReport::default();
+
+    for cart in self.shopping_cart_repo.get_carts_paid_today()? {
+      let cart_id = cart.id();
+      let mut skipped = vec![];
+
+      let result = self.process(cart, &mut skipped);
+
+      report.record(cart_id, skipped, result);
+    }
+
+    Ok(report)
+  }
+
+  fn process(&self, mut cart: ShoppingCart, skipped: &mut Vec<Step>) -> Result<(), StepError> {
+    let progress = self
+      .shopping_cart_repo
+      .progress(cart.id())
+      .map_err(|error| StepError::Progress {
+        step: Step::Deliver,
+        error,
+      })?;
+
+    if progress.saved {
+      skipped.extend([Step::Deliver, Step::Save]);
+      return Ok(());
+    }
+
+    let estimated_delivery_day = match progress.delivered {
+      Some(estimated_delivery_day) => {
+        skipped.push(Step::Deliver);
+        estimated_delivery_day
+      }
+      None => {
+        let estimated_delivery_day = self.delivery_center.deliver(&cart)?;
+
+        // A crash before this is recorded delivers the cart again.
+        self
+          .shopping_cart_repo
+          .record_delivery(cart.id(), estimated_delivery_day)
+          .map_err(|error| StepError::Progress {
+            step: Step::Deliver,
+            error,
+          })?;
+
+        estimated_delivery_day
+      }
+    };
+
+    cart.mask_as_ready_for_delivery(estimated_delivery_day);
+
+    // The notifier and SAP are told by the outbox dispatcher, so they only
+    // hear about carts that were actually saved.
+    let event = CartReadyForDelivery { cart: cart.clone() };
+    self.shopping_cart_repo.save(&cart, vec![event])?;
+
+    Ok(())
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CartStatus {
+  Paid,
+  ReadyForDelivery { estimated_delivery_day: u8 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShoppingCart {
+  id: u64,
+  status: CartStatus,
+}
+
+impl ShoppingCart {
+  /// A cart that has been paid and is waiting to be delivered.
+  pub fn new(id: u64) -> Self {
+    Self {
+      id,
+      status: CartStatus::Paid,
+    }
+  }
+
+  pub fn id(&self) -> u64 {
+    self.id
+  }
+
+  pub fn status(&self) -> CartStatus {
+    self.status
+  }
+
+  pub fn mask_as_ready_for_delivery(&mut self, estimated_delivery_day: u8) {
+    self.status = CartStatus::ReadyForDelivery {
+      estimated_delivery_day,
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use delivery::MockDeliveryCenter;
+  use mockall::predicate::eq;
+  use mockall::Sequence;
+  use repositories::MockShoppinCartRepository;
+
+  #[test]
+  fn delivers_and_saves_every_cart_paid_today_with_its_event() {
+    let mut ready = ShoppingCart::new(1);
+    ready.mask_as_ready_for_delivery(3);
+
+    let mut sequence = Sequence::new();
+
+    let mut shopping_cart_repo = MockShoppinCartRepository::new();
+    let mut delivery_center = MockDeliveryCenter::new();
+
+    shopping_cart_repo
+      .expect_get_carts_paid_today()
+      .times(1)
+      .in_sequence(&mut sequence)
+      .return_const(Ok(vec![ShoppingCart::new(1)]));
+
+    shopping_cart_repo
+      .expect_progress()
+      .with(eq(1))
+      .times(1)
+      .in_sequence(&mut sequence)
+      .return_const(Ok(CartProgress::default()));
+
+    delivery_center
+      .expect_deliver()
+      .with(eq(ShoppingCart::new(1)))
+      .times(1)
+      .in_sequence(&mut sequence)
+      .return_const(Ok(3));
+
+    shopping_cart_repo
+      .expect_record_delivery()
+      .with(eq(1), eq(3))
+      .times(1)
+      .in_sequence(&mut sequence)
+      .return_const(Ok(()));
+
+    shopping_cart_repo
+      .expect_save()
+      .with(
+        eq(ready.clone()),
+        eq(vec![CartReadyForDelivery { cart: ready }]),
+      )
+      .times(1)
+      .in_sequence(&mut sequence)
+      .return_const(Ok(()));
+
+    let report = PaidShoppingCartsBatch::builder()
+      .shopping_cart_repo(Box::new(shopping_cart_repo))
+      .delivery_center(Box::new(delivery_center))
+      .build()
+      .process_all();
+
+    assert_eq!(vec![1], report.unwrap().succeeded());
+  }
+
+  #[test]
+  fn carries_on_after_a_failed_cart() {
+    let mut shopping_cart_repo = MockShoppinCartRepository::new();
+    let mut delivery_center = MockDeliveryCenter::new();
+
+    shopping_cart_repo
+      .expect_get_carts_paid_today()
+      .return_const(Ok(vec![ShoppingCart::new(1), ShoppingCart::new(2)]));
+    shopping_cart_repo
+      .expect_progress()
+      .return_const(Ok(CartProgress::default()));
+    shopping_cart_repo
+      .expect_record_delivery()
+      .return_const(Ok(()));
+    delivery_center.expect_deliver().return_const(Ok(3));
+    shopping_cart_repo
+      .expect_save()
+      .times(2)
+      .returning(|cart, _| match cart.id() {
+        1 => Err(RepositoryError("timeout".to_string())),
+        _ => Ok(()),
+      });
+
+    let report = PaidShoppingCartsBatch::builder()
+      .shopping_cart_repo(Box::new(shopping_cart_repo))
+      .delivery_center(Box::new(delivery_center))
+      .build()
+      .process_all()
+      .unwrap();
+
+    assert!(!report.is_success());
+    assert_eq!(vec![2], report.succeeded());
+    assert_eq!(
+      vec![(1, &StepError::Save(RepositoryError("timeout".to_string())))],
+      report.failed()
+    );
+    assert_eq!(Step::Save, report.failed()[0].1.step());
+  }
+
+  #[test]
+  fn fails_when_the_carts_cannot_be_loaded() {
+    let mut shopping_cart_repo = MockShoppinCartRepository::new();
+
+    shopping_cart_repo
+      .expect_get_carts_paid_today()
+      .return_const(Err(RepositoryError("unavailable".to_string())));
+
+    let result = PaidShoppingCartsBatch::builder()
+      .shopping_cart_repo(Box::new(shopping_cart_repo))
+      .delivery_center(Box::new(MockDeliveryCenter::new()))
+      .build()
+      .process_all();
+
+    assert_eq!(Err(RepositoryError("unavailable".to_string())), result);
+  }
+
+  #[test]
+  fn skips_the_steps_of_a_previous_run() {
+    let mut ready = ShoppingCart::new(1);
+    ready.mask_as_ready_for_delivery(5);
+
+    let mut shopping_cart_repo = MockShoppinCartRepository::new();
+
+    shopping_cart_repo
+      .expect_get_carts_paid_today()
+      .return_const(Ok(vec![ShoppingCart::new(1), ShoppingCart::new(2)]));
+    shopping_cart_repo
+      .expect_progress()
+      .with(eq(1))
+      .return_const(Ok(CartProgress {
+        delivered: Some(5),
+        ..CartProgress::default()
+      }));
+    shopping_cart_repo
+      .expect_progress()
+      .with(eq(2))
+      .return_const(Ok(CartProgress {
+        delivered: Some(3),
+        saved: true,
+        ..CartProgress::default()
+      }));
+    shopping_cart_repo
+      .expect_save()
+      .with(
+        eq(ready.clone()),
+        eq(vec![CartReadyForDelivery { cart: ready }]),
+      )
+      .times(1)
+      .return_const(Ok(()));
+
+    // No expectations: the delivery center must not be called again.
+    let report = PaidShoppingCartsBatch::builder()
+      .shopping_cart_repo(Box::new(shopping_cart_repo))
+      .delivery_center(Box::new(MockDeliveryCenter::new()))
+      .build()
+      .process_all()
+      .unwrap();
+
+    assert_eq!(vec![1, 2], report.succeeded());
+    assert_eq!(vec![Step::Deliver], report.outcomes()[0].skipped);
+    assert_eq!(
+      vec![Step::Deliver, Step::Save],
+      report.outcomes()[1].skipped
+    );
+  }
+
+  #[test]
+  fn a_cart_whose_progress_cannot_be_tracked_fails() {
+    let mut shopping_cart_repo = MockShoppinCartRepository::new();
+    let mut delivery_center = MockDeliveryCenter::new();
+
+    shopping_cart_repo
+      .expect_get_carts_paid_today()
+      .return_const(Ok(vec![ShoppingCart::new(1)]));
+    shopping_cart_repo
+      .expect_progress()
+      .return_const(Ok(CartProgress::default()));
+    delivery_center.expect_deliver().return_const(Ok(3));
+    shopping_cart_repo
+      .expect_record_delivery()
+      .return_const(Err(RepositoryError("timeout".to_string())));
+
+    let report = PaidShoppingCartsBatch::builder()
+      .shopping_cart_repo(Box::new(shopping_cart_repo))
+      .delivery_center(Box::new(delivery_center))
+      .build()
+      .process_all()
+      .unwrap();
+
+    assert_eq!(
+      vec![(
+        1,
+        &StepError::Progress {
+          step: Step::Deliver,
+          error: RepositoryError("timeout".to_string())
+        }
+      )],
+      report.failed()
+    );
+  }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+
+use crate::ShoppingCart;
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+#[error("notification failed: {0}")]
+pub struct NotificationError(pub String);
+
+#[cfg_attr(test, mockall::automock)]
+pub trait Notifier {
+  /// Sends an e-mail to the customer with the estimated delivery day.
+  fn send_estimated_delivery_notification(
+    &self,
+    cart: &ShoppingCart,
+  ) -> Result<(), NotificationError>;
+}
+
+/// Records every notification instead of sending it.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryNotifier {
+  state: Arc<Mutex<NotifierState>>,
+}
+
+#[derive(Debug, Default)]
+struct NotifierState {
+  failing: HashSet<u64>,
+  sent: Vec<ShoppingCart>,
+}
+
+impl InMemoryNotifier {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Makes notifications about the cart with the given id fail.
+  pub fn fail_for(&self, cart_id: u64) {
+    self.state.lock().unwrap().failing.insert(cart_id);
+  }
+
+  /// Undoes [`InMemoryNotifier::fail_for`].
+  pub fn recover(&self) {
+    self.state.lock().unwrap().failing.clear();
+  }
+
+  /// The cart of every notification that was sent, in order.
+  pub fn sent(&self) -> Vec<ShoppingCart> {
+    self.state.lock().unwrap().sent.clone()
+  }
+}
+
+impl Notifier for InMemoryNotifier {
+  fn send_estimated_delivery_notification(
+    &self,
+    cart: &ShoppingCart,
+  ) -> Result<(), NotificationError> {
+    let mut state = self.state.lock().unwrap();
+
+    if state.failing.contains(&cart.id()) {
+      return Err(NotificationError(format!(
+        "cannot notify about cart {}",
+        cart.id()
+      )));
+    }
+
+    state.sent.push(cart.clone());
+
+    Ok(())
+  }
+}
@@ -0,0 +1,291 @@
+//! A transactional outbox for the side effects of a cart being ready for
+//! delivery.
+//!
+//! The repository stores the cart and its [`CartReadyForDelivery`] events in
+//! the same write, so an event exists if and only if the cart was saved. The
+//! [`OutboxDispatcher`] then delivers every pending event to the [`Notifier`]
+//! and to [`Sap`], and marks it as dispatched for each of them once they
+//! accepted it. An event is delivered at least once: a crash between the
+//! delivery and the marking delivers it again on the next run.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::report::{DispatchError, DispatchReport};
+use crate::{Notifier, RepositoryError, Sap, ShoppingCart};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CartReadyForDelivery {
+  pub cart: ShoppingCart,
+}
+
+/// Where the events of the outbox are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Destination {
+  Notifier,
+  Sap,
+}
+
+impl Destination {
+  pub const ALL: [Destination; 2] = [Destination::Notifier, Destination::Sap];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+  pub id: u64,
+  pub event: CartReadyForDelivery,
+  /// The destinations that have not accepted the event yet.
+  pub pending: BTreeSet<Destination>,
+}
+
+impl OutboxEntry {
+  /// An entry that is pending for every destination.
+  pub fn new(id: u64, event: CartReadyForDelivery) -> Self {
+    Self {
+      id,
+      event,
+      pending: Destination::ALL.into_iter().collect(),
+    }
+  }
+
+  pub fn is_dispatched(&self) -> bool {
+    self.pending.is_empty()
+  }
+}
+
+#[cfg_attr(test, mockall::automock)]
+pub trait Outbox {
+  /// The entries that are not dispatched to every destination, oldest first.
+  fn pending(&self) -> Result<Vec<OutboxEntry>, RepositoryError>;
+
+  fn mark_dispatched(&self, id: u64, destination: Destination) -> Result<(), RepositoryError>;
+}
+
+#[derive(TypedBuilder)]
+pub struct OutboxDispatcher {
+  outbox: Box<dyn Outbox>,
+  notifier: Box<dyn Notifier>,
+  sap: Box<dyn Sap>,
+  /// How many times an event is sent to a destination in a single run before
+  /// leaving it pending for the next run.
+  #[builder(default = 3)]
+  max_attempts: u32,
+}
+
+impl OutboxDispatcher {
+  /// Delivers every pending event to the destinations that have not accepted
+  /// it yet, carrying on with the next one when one fails.
+  ///
+  /// Fails only if the pending events cannot be loaded.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `max_attempts` is 0.
+  pub fn dispatch_pending(&self) -> Result<DispatchReport, RepositoryError> {
+    assert!(self.max_attempts > 0, "max_attempts must be at least 1");
+
+    let mut report = DispatchReport::default();
+
+    for entry in self.outbox.pending()? {
+      for &destination in &entry.pending {
+        let (attempts, result) = self.dispatch(&entry.event, destination);
+
+        let result = result.and_then(|_| {
+          self
+            .outbox
+            .mark_dispatched(entry.id, destination)
+            .map_err(DispatchError::from)
+        });
+
+        report.record(entry.id, destination, attempts, result);
+      }
+    }
+
+    Ok(report)
+  }
+
+  fn dispatch(
+    &self,
+    event: &CartReadyForDelivery,
+    destination: Destination,
+  ) -> (u32, Result<(), DispatchError>) {
+    let mut attempts = 0;
+
+    loop {
+      attempts += 1;
+
+      let result = match destination {
+        Destination::Notifier => self
+          .notifier
+          .send_estimated_delivery_notification(&event.cart)
+          .map_err(DispatchError::from),
+        Destination::Sap => self
+          .sap
+          .cart_ready_for_delivery(&event.cart)
+          .map_err(DispatchError::from),
+      };
+
+      if result.is_ok() || attempts == self.max_attempts {
+        return (attempts, result);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::notifications::MockNotifier;
+  use crate::sap::MockSap;
+  use crate::{NotificationError, SapError};
+  use mockall::predicate::eq;
+
+  fn entry(id: u64, pending: &[Destination]) -> OutboxEntry {
+    OutboxEntry {
+      pending: pending.iter().copied().collect(),
+      ..OutboxEntry::new(
+        id,
+        CartReadyForDelivery {
+          cart: ShoppingCart::new(id),
+        },
+      )
+    }
+  }
+
+  #[test]
+  fn delivers_pending_events_and_marks_them() {
+    let mut outbox = MockOutbox::new();
+    let mut notifier = MockNotifier::new();
+    let mut sap = MockSap::new();
+
+    outbox.expect_pending().return_const(Ok(vec![
+      entry(1, &Destination::ALL),
+      entry(2, &[Destination::Sap]),
+    ]));
+    notifier
+      .expect_send_estimated_delivery_notification()
+      .with(eq(ShoppingCart::new(1)))
+      .times(1)
+      .return_const(Ok(()));
+    sap
+      .expect_cart_ready_for_delivery()
+      .times(2)
+      .return_const(Ok(()));
+    outbox
+      .expect_mark_dispatched()
+      .times(3)
+      .return_const(Ok(()));
+
+    let report = OutboxDispatcher::builder()
+      .outbox(Box::new(outbox))
+      .notifier(Box::new(notifier))
+      .sap(Box::new(sap))
+      .build()
+      .dispatch_pending()
+      .unwrap();
+
+    assert!(report.is_success());
+    assert_eq!(
+      vec![
+        (1, Destination::Notifier),
+        (1, Destination::Sap),
+        (2, Destination::Sap)
+      ],
+      report.dispatched()
+    );
+  }
+
+  #[test]
+  fn retries_and_leaves_failed_destinations_pending() {
+    let mut outbox = MockOutbox::new();
+    let mut notifier = MockNotifier::new();
+    let mut sap = MockSap::new();
+    let mut failures = 1;
+
+    outbox
+      .expect_pending()
+      .return_const(Ok(vec![entry(1, &Destination::ALL)]));
+    notifier
+      .expect_send_estimated_delivery_notification()
+      .times(2)
+      .returning(move |_| {
+        if failures > 0 {
+          failures -= 1;
+          Err(NotificationError("timeout".to_string()))
+        } else {
+          Ok(())
+        }
+      });
+    sap
+      .expect_cart_ready_for_delivery()
+      .times(2)
+      .return_const(Err(SapError("down".to_string())));
+    outbox
+      .expect_mark_dispatched()
+      .with(eq(1), eq(Destination::Notifier))
+      .times(1)
+      .return_const(Ok(()));
+
+    let report = OutboxDispatcher::builder()
+      .outbox(Box::new(outbox))
+      .notifier(Box::new(notifier))
+      .sap(Box::new(sap))
+      .max_attempts(2)
+      .build()
+      .dispatch_pending()
+      .unwrap();
+
+    assert_eq!(vec![(1, Destination::Notifier)], report.dispatched());
+    assert_eq!(
+      vec![(
+        1,
+        Destination::Sap,
+        &DispatchError::UpdateSap(SapError("down".to_string()))
+      )],
+      report.failed()
+    );
+    assert_eq!(
+      vec![2, 2],
+      report
+        .outcomes()
+        .iter()
+        .map(|outcome| outcome.attempts)
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn a_failed_marking_is_reported() {
+    let mut outbox = MockOutbox::new();
+    let mut notifier = MockNotifier::new();
+
+    outbox
+      .expect_pending()
+      .return_const(Ok(vec![entry(1, &[Destination::Notifier])]));
+    notifier
+      .expect_send_estimated_delivery_notification()
+      .return_const(Ok(()));
+    outbox
+      .expect_mark_dispatched()
+      .return_const(Err(RepositoryError("disk full".to_string())));
+
+    let report = OutboxDispatcher::builder()
+      .outbox(Box::new(outbox))
+      .notifier(Box::new(notifier))
+      .sap(Box::new(MockSap::new()))
+      .build()
+      .dispatch_pending()
+      .unwrap();
+
+    assert_eq!(
+      vec![(
+        1,
+        Destination::Notifier,
+        &DispatchError::MarkDispatched(RepositoryError("disk full".to_string()))
+      )],
+      report.failed()
+    );
+  }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// The steps a paid cart went through, so that re-running the batch or the
+/// outbox dispatcher resumes where they stopped instead of starting over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CartProgress {
+  /// The estimated delivery day, once the delivery center accepted the cart.
+  pub delivered: Option<u8>,
+  /// The cart is ready for delivery and its event is in the outbox.
+  pub saved: bool,
+  pub notified: bool,
+  pub sap_updated: bool,
+}
+
+impl CartProgress {
+  pub fn is_complete(&self) -> bool {
+    self.delivered.is_some() && self.saved && self.notified && self.sap_updated
+  }
+}
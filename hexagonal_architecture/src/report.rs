@@ -0,0 +1,177 @@
+use thiserror::Error;
+
+use crate::outbox::Destination;
+use crate::{DeliveryError, NotificationError, RepositoryError, SapError};
+
+/// The steps of processing a paid shopping cart, in order. Notifying the
+/// customer and SAP happens later, through the outbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Step {
+  Deliver,
+  Save,
+}
+
+/// The step that failed while processing a cart and why.
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum StepError {
+  #[error(transparent)]
+  Deliver(#[from] DeliveryError),
+  #[error(transparent)]
+  Save(#[from] RepositoryError),
+  /// Whether the step already succeeded could not be read, or its success
+  /// could not be recorded.
+  #[error("cannot track the progress of {step:?}: {error}")]
+  Progress { step: Step, error: RepositoryError },
+}
+
+impl StepError {
+  pub fn step(&self) -> Step {
+    match self {
+      StepError::Deliver(_) => Step::Deliver,
+      StepError::Save(_) => Step::Save,
+      StepError::Progress { step, .. } => *step,
+    }
+  }
+}
+
+/// What happened to a single cart. A failed step stops the processing of
+/// that cart, so the steps after it were not attempted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CartOutcome {
+  pub cart_id: u64,
+  /// The steps that succeeded in a previous run and were not repeated.
+  pub skipped: Vec<Step>,
+  pub result: Result<(), StepError>,
+}
+
+/// The outcome of every cart processed by
+/// [`crate::PaidShoppingCartsBatch::process_all`], in processing order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchReport {
+  outcomes: Vec<CartOutcome>,
+}
+
+impl BatchReport {
+  pub(crate) fn record(&mut self, cart_id: u64, skipped: Vec<Step>, result: Result<(), StepError>) {
+    self.outcomes.push(CartOutcome {
+      cart_id,
+      skipped,
+      result,
+    });
+  }
+
+  pub fn outcomes(&self) -> &[CartOutcome] {
+    &self.outcomes
+  }
+
+  /// Tells whether every cart was processed without errors.
+  pub fn is_success(&self) -> bool {
+    self.outcomes.iter().all(|outcome| outcome.result.is_ok())
+  }
+
+  /// The ids of the carts that went through every step.
+  pub fn succeeded(&self) -> Vec<u64> {
+    self
+      .outcomes
+      .iter()
+      .filter(|outcome| outcome.result.is_ok())
+      .map(|outcome| outcome.cart_id)
+      .collect()
+  }
+
+  /// The id of every cart that failed, with the error of the failed step.
+  pub fn failed(&self) -> Vec<(u64, &StepError)> {
+    self
+      .outcomes
+      .iter()
+      .filter_map(|outcome| {
+        outcome
+          .result
+          .as_ref()
+          .err()
+          .map(|error| (outcome.cart_id, error))
+      })
+      .collect()
+  }
+}
+
+/// Why an outbox event was not dispatched to a destination.
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum DispatchError {
+  #[error(transparent)]
+  Notify(#[from] NotificationError),
+  #[error(transparent)]
+  UpdateSap(#[from] SapError),
+  /// The destination accepted the event but it is still pending, so it will
+  /// be delivered again.
+  #[error("cannot mark the event as dispatched: {0}")]
+  MarkDispatched(#[from] RepositoryError),
+}
+
+/// What happened to an outbox event for a single destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchOutcome {
+  pub event_id: u64,
+  pub destination: Destination,
+  pub attempts: u32,
+  pub result: Result<(), DispatchError>,
+}
+
+/// The outcome of every delivery attempted by
+/// [`crate::OutboxDispatcher::dispatch_pending`], in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchReport {
+  outcomes: Vec<DispatchOutcome>,
+}
+
+impl DispatchReport {
+  pub(crate) fn record(
+    &mut self,
+    event_id: u64,
+    destination: Destination,
+    attempts: u32,
+    result: Result<(), DispatchError>,
+  ) {
+    self.outcomes.push(DispatchOutcome {
+      event_id,
+      destination,
+      attempts,
+      result,
+    });
+  }
+
+  pub fn outcomes(&self) -> &[DispatchOutcome] {
+    &self.outcomes
+  }
+
+  /// Tells whether every pending event was dispatched.
+  pub fn is_success(&self) -> bool {
+    self.outcomes.iter().all(|outcome| outcome.result.is_ok())
+  }
+
+  /// The event id and destination of every successful delivery.
+  pub fn dispatched(&self) -> Vec<(u64, Destination)> {
+    self
+      .outcomes
+      .iter()
+      .filter(|outcome| outcome.result.is_ok())
+      .map(|outcome| (outcome.event_id, outcome.destination))
+      .collect()
+  }
+
+  /// The event id and destination of every failed delivery, with the error
+  /// of the last attempt.
+  pub fn failed(&self) -> Vec<(u64, Destination, &DispatchError)> {
+    self
+      .outcomes
+      .iter()
+      .filter_map(|outcome| {
+        outcome
+          .result
+          .as_ref()
+          .err()
+          .map(|error| (outcome.event_id, outcome.destination, error))
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::outbox::{CartReadyForDelivery, Destination, Outbox, OutboxEntry};
+use crate::{CartProgress, CartStatus, ShoppingCart};
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+#[error("shopping cart repository failed: {0}")]
+pub struct RepositoryError(pub String);
+
+#[cfg_attr(test, mockall::automock)]
+pub trait ShoppinCartRepository {
+  fn get_carts_paid_today(&self) -> Result<Vec<ShoppingCart>, RepositoryError>;
+
+  /// Saves the cart and appends `events` to the outbox in the same write, so
+  /// either both are stored or neither is. Saving a cart that is ready for
+  /// delivery records it as saved in its progress, in the same write too.
+  fn save(
+    &self,
+    cart: &ShoppingCart,
+    events: Vec<CartReadyForDelivery>,
+  ) -> Result<(), RepositoryError>;
+
+  /// The steps the cart with the given id went through. Notifying the
+  /// customer and SAP are recorded when the outbox entry is marked as
+  /// dispatched.
+  fn progress(&self, cart_id: u64) -> Result<CartProgress, RepositoryError>;
+
+  /// Records that the delivery center accepted the cart with the given id.
+  fn record_delivery(
+    &self,
+    cart_id: u64,
+    estimated_delivery_day: u8,
+  ) -> Result<(), RepositoryError>;
+}
+
+/// The carts and the outbox, as stored by the repositories of this crate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct Store {
+  carts: BTreeMap<u64, ShoppingCart>,
+  paid_today: BTreeSet<u64>,
+  outbox: Vec<OutboxEntry>,
+  next_event_id: u64,
+  #[serde(default)]
+  progress: BTreeMap<u64, CartProgress>,
+}
+
+impl Store {
+  pub(crate) fn add_paid_today(&mut self, cart: ShoppingCart) {
+    self.paid_today.insert(cart.id());
+    self.carts.insert(cart.id(), cart);
+  }
+
+  pub(crate) fn get(&self, id: u64) -> Option<&ShoppingCart> {
+    self.carts.get(&id)
+  }
+
+  pub(crate) fn carts_paid_today(&self) -> Vec<ShoppingCart> {
+    self
+      .paid_today
+      .iter()
+      .map(|id| self.carts[id].clone())
+      .collect()
+  }
+
+  pub(crate) fn save(&mut self, cart: &ShoppingCart, events: Vec<CartReadyForDelivery>) {
+    self.carts.insert(cart.id(), cart.clone());
+
+    if let CartStatus::ReadyForDelivery {
+      estimated_delivery_day,
+    } = cart.status()
+    {
+      let progress = self.progress.entry(cart.id()).or_default();
+
+      progress.delivered.get_or_insert(estimated_delivery_day);
+      progress.saved = true;
+    }
+
+    for event in events {
+      self.next_event_id += 1;
+      self
+        .outbox
+        .push(OutboxEntry::new(self.next_event_id, event));
+    }
+  }
+
+  pub(crate) fn outbox(&self) -> &[OutboxEntry] {
+    &self.outbox
+  }
+
+  pub(crate) fn pending(&self) -> Vec<OutboxEntry> {
+    self
+      .outbox
+      .iter()
+      .filter(|entry| !entry.is_dispatched())
+      .cloned()
+      .collect()
+  }
+
+  pub(crate) fn mark_dispatched(
+    &mut self,
+    id: u64,
+    destination: Destination,
+  ) -> Result<(), RepositoryError> {
+    let entry = self
+      .outbox
+      .iter_mut()
+      .find(|entry| entry.id == id)
+      .ok_or_else(|| RepositoryError(format!("no outbox entry {}", id)))?;
+
+    entry.pending.remove(&destination);
+
+    let progress = self.progress.entry(entry.event.cart.id()).or_default();
+
+    match destination {
+      Destination::Notifier => progress.notified = true,
+      Destination::Sap => progress.sap_updated = true,
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn progress(&self, cart_id: u64) -> CartProgress {
+    self.progress.get(&cart_id).copied().unwrap_or_default()
+  }
+
+  pub(crate) fn record_delivery(&mut self, cart_id: u64, estimated_delivery_day: u8) {
+    self.progress.entry(cart_id).or_default().delivered = Some(estimated_delivery_day);
+  }
+}
+
+/// Keeps the carts and the outbox in memory and records every cart that is
+/// saved.
+///
+/// Clones share the same carts, so a test can keep a clone to inspect what
+/// the code under test did.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryShoppinCartRepository {
+  state: Arc<Mutex<RepositoryState>>,
+}
+
+#[derive(Debug, Default)]
+struct RepositoryState {
+  store: Store,
+  saved: Vec<ShoppingCart>,
+  unavailable: bool,
+  failing_saves: BTreeSet<u64>,
+}
+
+impl RepositoryState {
+  fn check_available(&self) -> Result<(), RepositoryError> {
+    if self.unavailable {
+      return Err(RepositoryError("unavailable".to_string()));
+    }
+
+    Ok(())
+  }
+}
+
+impl InMemoryShoppinCartRepository {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Stores `cart` as a cart that was paid today.
+  pub fn add_paid_today(&self, cart: ShoppingCart) {
+    self.state.lock().unwrap().store.add_paid_today(cart);
+  }
+
+  /// Makes every call fail until [`InMemoryShoppinCartRepository::recover`].
+  pub fn make_unavailable(&self) {
+    self.state.lock().unwrap().unavailable = true;
+  }
+
+  /// Makes saving the cart with the given id fail.
+  pub fn fail_to_save(&self, cart_id: u64) {
+    self.state.lock().unwrap().failing_saves.insert(cart_id);
+  }
+
+  /// Undoes [`InMemoryShoppinCartRepository::make_unavailable`] and
+  /// [`InMemoryShoppinCartRepository::fail_to_save`].
+  pub fn recover(&self) {
+    let mut state = self.state.lock().unwrap();
+
+    state.unavailable = false;
+    state.failing_saves.clear();
+  }
+
+  /// The current state of the cart with the given id.
+  pub fn get(&self, id: u64) -> Option<ShoppingCart> {
+    self.state.lock().unwrap().store.get(id).cloned()
+  }
+
+  /// Every cart successfully passed to [`ShoppinCartRepository::save`], in
+  /// order.
+  pub fn saved(&self) -> Vec<ShoppingCart> {
+    self.state.lock().unwrap().saved.clone()
+  }
+
+  /// Every entry of the outbox, dispatched or not, in order.
+  pub fn outbox(&self) -> Vec<OutboxEntry> {
+    self.state.lock().unwrap().store.outbox().to_vec()
+  }
+}
+
+impl ShoppinCartRepository for InMemoryShoppinCartRepository {
+  fn get_carts_paid_today(&self) -> Result<Vec<ShoppingCart>, RepositoryError> {
+    let state = self.state.lock().unwrap();
+
+    state.check_available()?;
+
+    Ok(state.store.carts_paid_today())
+  }
+
+  fn save(
+    &self,
+    cart: &ShoppingCart,
+    events: Vec<CartReadyForDelivery>,
+  ) -> Result<(), RepositoryError> {
+    let mut state = self.state.lock().unwrap();
+
+    state.check_available()?;
+
+    if state.failing_saves.contains(&cart.id()) {
+      return Err(RepositoryError(format!("cannot save cart {}", cart.id())));
+    }
+
+    state.store.save(cart, events);
+    state.saved.push(cart.clone());
+
+    Ok(())
+  }
+
+  fn progress(&self, cart_id: u64) -> Result<CartProgress, RepositoryError> {
+    let state = self.state.lock().unwrap();
+
+    state.check_available()?;
+
+    Ok(state.store.progress(cart_id))
+  }
+
+  fn record_delivery(
+    &self,
+    cart_id: u64,
+    estimated_delivery_day: u8,
+  ) -> Result<(), RepositoryError> {
+    let mut state = self.state.lock().unwrap();
+
+    state.check_available()?;
+
+    state.store.record_delivery(cart_id, estimated_delivery_day);
+
+    Ok(())
+  }
+}
+
+impl Outbox for InMemoryShoppinCartRepository {
+  fn pending(&self) -> Result<Vec<OutboxEntry>, RepositoryError> {
+    let state = self.state.lock().unwrap();
+
+    state.check_available()?;
+
+    Ok(state.store.pending())
+  }
+
+  fn mark_dispatched(&self, id: u64, destination: Destination) -> Result<(), RepositoryError> {
+    let mut state = self.state.lock().unwrap();
+
+    state.check_available()?;
+
+    state.store.mark_dispatched(id, destination)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ready_for_delivery(id: u64) -> ShoppingCart {
+    let mut cart = ShoppingCart::new(id);
+    cart.mask_as_ready_for_delivery(3);
+    cart
+  }
+
+  #[test]
+  fn saves_carts_and_records_them() {
+    let repository = InMemoryShoppinCartRepository::new();
+
+    repository.add_paid_today(ShoppingCart::new(2));
+    repository.add_paid_today(ShoppingCart::new(1));
+
+    assert_eq!(
+      Ok(vec![ShoppingCart::new(1), ShoppingCart::new(2)]),
+      repository.get_carts_paid_today()
+    );
+
+    let cart = ready_for_delivery(1);
+
+    // Clones share the carts.
+    repository.clone().save(&cart, vec![]).unwrap();
+
+    assert_eq!(Some(cart.clone()), repository.get(1));
+    assert_eq!(vec![cart.clone()], repository.saved());
+    assert_eq!(cart, repository.get_carts_paid_today().unwrap()[0]);
+  }
+
+  #[test]
+  fn saving_appends_events_to_the_outbox() {
+    let repository = InMemoryShoppinCartRepository::new();
+
+    for id in 1..=2 {
+      let cart = ready_for_delivery(id);
+      let event = CartReadyForDelivery { cart: cart.clone() };
+
+      repository.save(&cart, vec![event]).unwrap();
+    }
+
+    let pending = repository.pending().unwrap();
+
+    assert_eq!(vec![1, 2], pending.iter().map(|e| e.id).collect::<Vec<_>>());
+    assert_eq!(ready_for_delivery(2), pending[1].event.cart);
+
+    repository
+      .mark_dispatched(1, Destination::Notifier)
+      .unwrap();
+    assert_eq!(2, repository.pending().unwrap().len());
+
+    repository.mark_dispatched(1, Destination::Sap).unwrap();
+    assert_eq!(vec![2], {
+      let pending = repository.pending().unwrap();
+      pending.iter().map(|e| e.id).collect::<Vec<_>>()
+    });
+    assert!(repository.outbox()[0].is_dispatched());
+
+    assert!(repository.mark_dispatched(3, Destination::Sap).is_err());
+  }
+
+  #[test]
+  fn a_failed_save_does_not_touch_the_outbox() {
+    let repository = InMemoryShoppinCartRepository::new();
+    let cart = ready_for_delivery(1);
+
+    repository.add_paid_today(ShoppingCart::new(1));
+    repository.fail_to_save(1);
+
+    assert_eq!(
+      Err(RepositoryError("cannot save cart 1".to_string())),
+      repository.save(&cart, vec![CartReadyForDelivery { cart: cart.clone() }])
+    );
+    assert_eq!(Some(ShoppingCart::new(1)), repository.get(1));
+    assert!(repository.outbox().is_empty());
+
+    repository.make_unavailable();
+    assert!(repository.get_carts_paid_today().is_err());
+    assert!(repository.pending().is_err());
+
+    repository.recover();
+    assert_eq!(Ok(()), repository.save(&cart, vec![]));
+    assert_eq!(vec![cart], repository.saved());
+  }
+
+  #[test]
+  fn records_the_progress_of_every_step() {
+    let repository = InMemoryShoppinCartRepository::new();
+    let cart = ready_for_delivery(1);
+
+    assert_eq!(Ok(CartProgress::default()), repository.progress(1));
+
+    repository.record_delivery(1, 3).unwrap();
+    assert_eq!(Some(3), repository.progress(1).unwrap().delivered);
+    assert!(!repository.progress(1).unwrap().saved);
+
+    repository
+      .save(&cart, vec![CartReadyForDelivery { cart: cart.clone() }])
+      .unwrap();
+    repository.mark_dispatched(1, Destination::Sap).unwrap();
+
+    assert_eq!(
+      Ok(CartProgress {
+        delivered: Some(3),
+        saved: true,
+        notified: false,
+        sap_updated: true,
+      }),
+      repository.progress(1)
+    );
+
+    repository
+      .mark_dispatched(1, Destination::Notifier)
+      .unwrap();
+    assert!(repository.progress(1).unwrap().is_complete());
+    assert_eq!(Ok(CartProgress::default()), repository.progress(2));
+  }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use thiserror::Error;
+
+use crate::ShoppingCart;
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+#[error("SAP failed: {0}")]
+pub struct SapError(pub String);
+
+#[cfg_attr(test, mockall::automock)]
+pub trait Sap {
+  fn cart_ready_for_delivery(&self, cart: &ShoppingCart) -> Result<(), SapError>;
+}
+
+/// Records every cart that SAP is told about.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySap {
+  state: Arc<Mutex<SapState>>,
+}
+
+#[derive(Debug, Default)]
+struct SapState {
+  failing: HashSet<u64>,
+  ready_for_delivery: Vec<ShoppingCart>,
+}
+
+impl InMemorySap {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Makes updates about the cart with the given id fail.
+  pub fn fail_for(&self, cart_id: u64) {
+    self.state.lock().unwrap().failing.insert(cart_id);
+  }
+
+  /// Undoes [`InMemorySap::fail_for`].
+  pub fn recover(&self) {
+    self.state.lock().unwrap().failing.clear();
+  }
+
+  /// Every cart successfully passed to [`Sap::cart_ready_for_delivery`], in
+  /// order.
+  pub fn ready_for_delivery(&self) -> Vec<ShoppingCart> {
+    self.state.lock().unwrap().ready_for_delivery.clone()
+  }
+}
+
+impl Sap for InMemorySap {
+  fn cart_ready_for_delivery(&self, cart: &ShoppingCart) -> Result<(), SapError> {
+    let mut state = self.state.lock().unwrap();
+
+    if state.failing.contains(&cart.id()) {
+      return Err(SapError(format!("cannot update cart {}", cart.id())));
+    }
+
+    state.ready_for_delivery.push(cart.clone());
+
+    Ok(())
+  }
+}
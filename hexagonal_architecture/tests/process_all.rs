@@ -0,0 +1,295 @@
+use std::{env, fs};
+
+use hexagonal_architecture::{
+  CartStatus, Destination, FileShoppinCartRepository, InMemoryDeliveryCenter, InMemoryNotifier,
+  InMemorySap, InMemoryShoppinCartRepository, OutboxDispatcher, PaidShoppingCartsBatch,
+  ShoppinCartRepository, ShoppingCart, Step,
+};
+
+fn ready_for_delivery(id: u64, estimated_delivery_day: u8) -> ShoppingCart {
+  let mut cart = ShoppingCart::new(id);
+  cart.mask_as_ready_for_delivery(estimated_delivery_day);
+  cart
+}
+
+fn dispatcher(
+  repository: &InMemoryShoppinCartRepository,
+  notifier: &InMemoryNotifier,
+  sap: &InMemorySap,
+) -> OutboxDispatcher {
+  OutboxDispatcher::builder()
+    .outbox(Box::new(repository.clone()))
+    .notifier(Box::new(notifier.clone()))
+    .sap(Box::new(sap.clone()))
+    .build()
+}
+
+#[test]
+fn processes_every_cart_paid_today_with_in_memory_adapters() {
+  let repository = InMemoryShoppinCartRepository::new();
+  repository.add_paid_today(ShoppingCart::new(1));
+  repository.add_paid_today(ShoppingCart::new(2));
+
+  let delivery_center = InMemoryDeliveryCenter::new(3).with_estimate(2, 5);
+  let sap = InMemorySap::new();
+  let notifier = InMemoryNotifier::new();
+
+  let report = PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository.clone()))
+    .delivery_center(Box::new(delivery_center.clone()))
+    .build()
+    .process_all()
+    .unwrap();
+
+  assert!(report.is_success());
+
+  let ready = vec![ready_for_delivery(1, 3), ready_for_delivery(2, 5)];
+
+  assert_eq!(
+    vec![ShoppingCart::new(1), ShoppingCart::new(2)],
+    delivery_center.delivered()
+  );
+  assert_eq!(ready, repository.saved());
+  assert_eq!(
+    CartStatus::ReadyForDelivery {
+      estimated_delivery_day: 5
+    },
+    repository.get(2).unwrap().status()
+  );
+
+  // Nobody is told before the outbox is dispatched.
+  assert!(notifier.sent().is_empty());
+  assert_eq!(2, repository.outbox().len());
+
+  let report = dispatcher(&repository, &notifier, &sap)
+    .dispatch_pending()
+    .unwrap();
+
+  assert!(report.is_success());
+  assert_eq!(ready, notifier.sent());
+  assert_eq!(ready, sap.ready_for_delivery());
+  assert!(repository
+    .outbox()
+    .iter()
+    .all(|entry| entry.is_dispatched()));
+}
+
+#[test]
+fn does_nothing_without_carts_paid_today() {
+  let repository = InMemoryShoppinCartRepository::new();
+
+  let report = PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository.clone()))
+    .delivery_center(Box::new(InMemoryDeliveryCenter::new(1)))
+    .build()
+    .process_all()
+    .unwrap();
+
+  assert!(report.outcomes().is_empty());
+  assert!(repository.outbox().is_empty());
+}
+
+#[test]
+fn keeps_going_after_a_cart_fails() {
+  let repository = InMemoryShoppinCartRepository::new();
+  for id in 1..=3 {
+    repository.add_paid_today(ShoppingCart::new(id));
+  }
+
+  let delivery_center = InMemoryDeliveryCenter::new(3);
+
+  delivery_center.fail_for(1);
+  repository.fail_to_save(2);
+
+  let report = PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository.clone()))
+    .delivery_center(Box::new(delivery_center.clone()))
+    .build()
+    .process_all()
+    .unwrap();
+
+  assert_eq!(vec![3], report.succeeded());
+  assert_eq!(
+    vec![(1, Step::Deliver), (2, Step::Save)],
+    report
+      .failed()
+      .into_iter()
+      .map(|(id, error)| (id, error.step()))
+      .collect::<Vec<_>>()
+  );
+
+  // A cart that was not delivered is still paid, and only the saved cart has
+  // an event in the outbox.
+  assert_eq!(CartStatus::Paid, repository.get(1).unwrap().status());
+  assert_eq!(vec![ready_for_delivery(3, 3)], repository.saved());
+  assert_eq!(
+    vec![ready_for_delivery(3, 3)],
+    repository
+      .outbox()
+      .into_iter()
+      .map(|entry| entry.event.cart)
+      .collect::<Vec<_>>()
+  );
+}
+
+#[test]
+fn failed_deliveries_are_retried_on_the_next_dispatch() {
+  let repository = InMemoryShoppinCartRepository::new();
+  repository.add_paid_today(ShoppingCart::new(1));
+
+  PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository.clone()))
+    .delivery_center(Box::new(InMemoryDeliveryCenter::new(3)))
+    .build()
+    .process_all()
+    .unwrap();
+
+  let notifier = InMemoryNotifier::new();
+  let sap = InMemorySap::new();
+  let dispatcher = dispatcher(&repository, &notifier, &sap);
+
+  sap.fail_for(1);
+
+  let report = dispatcher.dispatch_pending().unwrap();
+
+  assert_eq!(vec![(1, Destination::Notifier)], report.dispatched());
+  assert_eq!(1, report.failed().len());
+  assert_eq!(3, report.outcomes()[1].attempts);
+
+  sap.recover();
+
+  let report = dispatcher.dispatch_pending().unwrap();
+
+  // The customer is not e-mailed twice.
+  assert_eq!(vec![(1, Destination::Sap)], report.dispatched());
+  assert_eq!(vec![ready_for_delivery(1, 3)], notifier.sent());
+  assert_eq!(vec![ready_for_delivery(1, 3)], sap.ready_for_delivery());
+
+  assert!(dispatcher.dispatch_pending().unwrap().outcomes().is_empty());
+}
+
+#[test]
+fn fails_when_the_repository_is_unavailable() {
+  let repository = InMemoryShoppinCartRepository::new();
+  repository.make_unavailable();
+
+  let result = PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository.clone()))
+    .delivery_center(Box::new(InMemoryDeliveryCenter::new(1)))
+    .build()
+    .process_all();
+
+  assert!(result.is_err());
+  assert!(
+    dispatcher(&repository, &InMemoryNotifier::new(), &InMemorySap::new())
+      .dispatch_pending()
+      .is_err()
+  );
+}
+
+#[test]
+fn the_batch_and_the_dispatcher_share_a_file_backed_outbox() {
+  let path = env::temp_dir().join(format!(
+    "hexagonal_architecture-outbox-{}.json",
+    std::process::id()
+  ));
+  let _ = fs::remove_file(&path);
+
+  let repository = FileShoppinCartRepository::new(&path);
+  repository.add_paid_today(ShoppingCart::new(1)).unwrap();
+
+  PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository))
+    .delivery_center(Box::new(InMemoryDeliveryCenter::new(4)))
+    .build()
+    .process_all()
+    .unwrap();
+
+  let notifier = InMemoryNotifier::new();
+  let sap = InMemorySap::new();
+
+  let report = OutboxDispatcher::builder()
+    .outbox(Box::new(FileShoppinCartRepository::new(&path)))
+    .notifier(Box::new(notifier.clone()))
+    .sap(Box::new(sap.clone()))
+    .build()
+    .dispatch_pending()
+    .unwrap();
+
+  assert!(report.is_success());
+  assert_eq!(vec![ready_for_delivery(1, 4)], notifier.sent());
+  assert_eq!(vec![ready_for_delivery(1, 4)], sap.ready_for_delivery());
+
+  let repository = FileShoppinCartRepository::new(&path);
+  assert_eq!(Some(ready_for_delivery(1, 4)), repository.get(1).unwrap());
+  assert!(repository.outbox().unwrap()[0].is_dispatched());
+
+  fs::remove_file(&path).unwrap();
+  fs::remove_file(path.with_extension("json.lock")).unwrap();
+}
+
+#[test]
+fn a_re_run_resumes_where_the_previous_run_stopped() {
+  let repository = InMemoryShoppinCartRepository::new();
+  for id in 1..=3 {
+    repository.add_paid_today(ShoppingCart::new(id));
+  }
+
+  let delivery_center = InMemoryDeliveryCenter::new(3);
+  let notifier = InMemoryNotifier::new();
+  let sap = InMemorySap::new();
+  let batch = PaidShoppingCartsBatch::builder()
+    .shopping_cart_repo(Box::new(repository.clone()))
+    .delivery_center(Box::new(delivery_center.clone()))
+    .build();
+  let dispatcher = dispatcher(&repository, &notifier, &sap);
+
+  // Cart 2 is delivered but not saved, cart 3 is not delivered.
+  repository.fail_to_save(2);
+  delivery_center.fail_for(3);
+  sap.fail_for(1);
+
+  batch.process_all().unwrap();
+  dispatcher.dispatch_pending().unwrap();
+
+  repository.recover();
+  delivery_center.recover();
+  sap.recover();
+
+  let report = batch.process_all().unwrap();
+
+  assert!(report.is_success());
+  assert_eq!(
+    vec![vec![Step::Deliver, Step::Save], vec![Step::Deliver], vec![]],
+    report
+      .outcomes()
+      .iter()
+      .map(|outcome| outcome.skipped.clone())
+      .collect::<Vec<_>>()
+  );
+
+  dispatcher.dispatch_pending().unwrap();
+
+  // Running everything once more does nothing.
+  batch.process_all().unwrap();
+  assert!(dispatcher.dispatch_pending().unwrap().outcomes().is_empty());
+
+  let ready = vec![
+    ready_for_delivery(1, 3),
+    ready_for_delivery(2, 3),
+    ready_for_delivery(3, 3),
+  ];
+
+  assert_eq!(
+    vec![
+      ShoppingCart::new(1),
+      ShoppingCart::new(2),
+      ShoppingCart::new(3)
+    ],
+    delivery_center.delivered()
+  );
+  assert_eq!(ready, repository.saved());
+  assert_eq!(ready, notifier.sent());
+  assert_eq!(ready, sap.ready_for_delivery());
+  assert!((1..=3).all(|id| repository.progress(id).unwrap().is_complete()));
+}